@@ -0,0 +1,70 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use libotp::{Digits, HOTPAlgorithm, TimeStep, HOTP, TOTP};
+
+const ALGORITHMS: [HOTPAlgorithm; 5] = [
+    HOTPAlgorithm::HMACSHA1,
+    HOTPAlgorithm::HMACSHA256,
+    HOTPAlgorithm::HMACSHA512,
+    HOTPAlgorithm::HMACSHA3_256,
+    HOTPAlgorithm::HMACSHA3_512,
+];
+
+fn hotp_get_otp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HOTP::get_otp");
+    for algorithm in ALGORITHMS {
+        let hotp = HOTP::new(algorithm).unwrap();
+        let counter = 1234u64.to_be_bytes();
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm), &hotp, |b, hotp| {
+            b.iter(|| hotp.get_otp(black_box(&counter), Digits::SIX))
+        });
+    }
+    group.finish();
+}
+
+fn totp_get_otp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("TOTP::get_otp");
+    for algorithm in ALGORITHMS {
+        let totp = TOTP::new(HOTP::new(algorithm).unwrap(), TimeStep::THIRTY_SECONDS, 0);
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm), &totp, |b, totp| {
+            b.iter(|| totp.get_otp(black_box(6), 0))
+        });
+    }
+    group.finish();
+}
+
+fn totp_verify_with_window(c: &mut Criterion) {
+    let mut group = c.benchmark_group("TOTP::verify_with_window");
+    for algorithm in ALGORITHMS {
+        let totp = TOTP::new(HOTP::new(algorithm).unwrap(), TimeStep::THIRTY_SECONDS, 0);
+        for window in [1, 10] {
+            // An unlikely code, so every time frame of the window is checked.
+            group.bench_with_input(BenchmarkId::new(algorithm.to_string(), window), &window, |b, window| {
+                b.iter(|| totp.verify_with_window(black_box(999_999_999), 6, *window))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn hotp_base32(c: &mut Criterion) {
+    let mut group = c.benchmark_group("HOTP::from_base32");
+    for algorithm in ALGORITHMS {
+        let encoded = HOTP::new(algorithm).unwrap().get_secret_base32();
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm), &encoded, |b, encoded| {
+            b.iter(|| HOTP::from_base32(black_box(encoded)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("HOTP::get_secret_base32");
+    for algorithm in ALGORITHMS {
+        let hotp = HOTP::new(algorithm).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm), &hotp, |b, hotp| {
+            b.iter(|| black_box(hotp).get_secret_base32())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, hotp_get_otp, totp_get_otp, totp_verify_with_window, hotp_base32);
+criterion_main!(benches);
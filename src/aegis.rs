@@ -0,0 +1,321 @@
+//! Import and export of Aegis Authenticator vaults, see
+//! <https://github.com/beemdevelopment/Aegis/blob/master/docs/vault.md>.
+
+use super::*;
+use serde::{Deserialize, Serialize};
+
+/// Version of the vault format written by `HOTP::to_aegis_json`.
+const VAULT_VERSION: u32 = 1;
+
+/// Version of the database format written by `HOTP::to_aegis_json`.
+const DATABASE_VERSION: u32 = 2;
+
+/// Slot type of a key slot protected by a password.
+const SLOT_TYPE_PASSWORD: u32 = 1;
+
+/// Highest scrypt cost parameters accepted from a vault, which bound the memory used to `128 * r * n` bytes, i.e.
+/// 4 GiB. Aegis writes `n = 2^15`, `r = 8` and `p = 1`.
+const SCRYPT_MAXIMUM_N: u64 = 1 << 20;
+const SCRYPT_MAXIMUM_R: u32 = 32;
+const SCRYPT_MAXIMUM_P: u32 = 16;
+
+#[derive(Serialize, Deserialize)]
+struct Vault {
+    version: u32,
+    header: Header,
+    db: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    slots: Option<Vec<Slot>>,
+    params: Option<KeyParams>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Slot {
+    #[serde(rename = "type")]
+    slot_type: u32,
+    key: String,
+    key_params: KeyParams,
+    n: Option<u64>,
+    r: Option<u32>,
+    p: Option<u32>,
+    salt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyParams {
+    nonce: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Database {
+    version: u32,
+    entries: Vec<Entry>,
+    #[serde(default)]
+    groups: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    #[serde(rename = "type")]
+    entry_type: String,
+    uuid: String,
+    name: String,
+    issuer: String,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default)]
+    icon: Option<String>,
+    info: Info,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Info {
+    secret: String,
+    algo: String,
+    digits: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    counter: Option<u64>,
+}
+
+impl HOTP {
+    /// Parses the OTP accounts of an unencrypted Aegis vault export.
+    ///
+    /// Returns `OtpError::InvalidVault` for encrypted vaults and for entry types other than TOTP and HOTP.
+    ///
+    /// # Arguments
+    /// * `json` - The exported vault.
+    ///
+    /// # Notes
+    /// Authenticator apps commonly use 10 byte secrets, so the minimum secret length is not enforced.
+    pub fn from_aegis_json(json: &str) -> Result<Vec<OtpUri>, OtpError> {
+        let vault: Vault = serde_json::from_str(json).map_err(|_| OtpError::InvalidVault)?;
+        if vault.header.slots.is_some() {
+            return Err(OtpError::InvalidVault);
+        }
+
+        let database = serde_json::from_value(vault.db).map_err(|_| OtpError::InvalidVault)?;
+        parse_database(database)
+    }
+
+    /// Parses the OTP accounts of an Aegis vault export encrypted with a password.
+    ///
+    /// Returns `OtpError::VaultDecryption` if no password slot of the vault can be opened with `password`, and
+    /// `OtpError::InvalidVault` if a slot asks for scrypt parameters above `n = 2^20`, `r = 32` or `p = 16`.
+    ///
+    /// # Arguments
+    /// * `json` - The exported vault.
+    /// * `password` - The password the vault was encrypted with.
+    pub fn from_aegis_json_encrypted(json: &str, password: &str) -> Result<Vec<OtpUri>, OtpError> {
+        let vault: Vault = serde_json::from_str(json).map_err(|_| OtpError::InvalidVault)?;
+        let (slots, params) = match (vault.header.slots, vault.header.params) {
+            (Some(slots), Some(params)) => (slots, params),
+            _ => {
+                return Err(OtpError::InvalidVault);
+            }
+        };
+        let db = match vault.db {
+            serde_json::Value::String(db) => decode_base64(&db)?,
+            _ => {
+                return Err(OtpError::InvalidVault);
+            }
+        };
+
+        let mut master_key = None;
+        for slot in slots.iter().filter(|slot| slot.slot_type == SLOT_TYPE_PASSWORD) {
+            if let Some(key) = open_password_slot(slot, password)? {
+                master_key = Some(key);
+                break;
+            }
+        }
+        let master_key = master_key.ok_or(OtpError::VaultDecryption)?;
+
+        let plaintext = decrypt(&master_key, &params, db)?;
+        let database = serde_json::from_slice(&plaintext).map_err(|_| OtpError::InvalidVault)?;
+        parse_database(database)
+    }
+
+    /// Exports OTP accounts as an unencrypted Aegis vault.
+    ///
+    /// Returns `OtpError::UnknownAlgorithm` for SHA3 secrets, as Aegis cannot represent them.
+    ///
+    /// # Arguments
+    /// * `entries` - The accounts to export.
+    pub fn to_aegis_json(entries: &[OtpUri]) -> Result<String, OtpError> {
+        let mut database = Database {
+            version: DATABASE_VERSION,
+            entries: Vec::with_capacity(entries.len()),
+            groups: Vec::new(),
+        };
+
+        for entry in entries {
+            let algo = match entry.secret.algorithm {
+                HOTPAlgorithm::HMACSHA1 | HOTPAlgorithm::HMACSHA256 | HOTPAlgorithm::HMACSHA512 => entry.secret.algorithm.to_string(),
+                HOTPAlgorithm::HMACSHA3_256 | HOTPAlgorithm::HMACSHA3_512 => {
+                    return Err(OtpError::UnknownAlgorithm);
+                }
+            };
+            let (entry_type, period, counter) = match entry.kind {
+                OtpUriKind::Totp { period } => ("totp", Some(period), None),
+                OtpUriKind::Hotp { counter } => ("hotp", None, Some(counter)),
+            };
+
+            database.entries.push(Entry {
+                entry_type: String::from(entry_type),
+                uuid: random_uuid()?,
+                name: entry.account.clone(),
+                issuer: entry.issuer.clone().unwrap_or_default(),
+                note: String::new(),
+                favorite: false,
+                icon: None,
+                info: Info {
+                    secret: String::from(entry.secret.get_secret_base32().trim_end_matches('=')),
+                    algo,
+                    digits: entry.digits.get(),
+                    period,
+                    counter,
+                },
+            });
+        }
+
+        let vault = Vault {
+            version: VAULT_VERSION,
+            header: Header {
+                slots: None,
+                params: None,
+            },
+            db: serde_json::to_value(database).map_err(|_| OtpError::InvalidVault)?,
+        };
+        serde_json::to_string(&vault).map_err(|_| OtpError::InvalidVault)
+    }
+}
+
+fn parse_database(database: Database) -> Result<Vec<OtpUri>, OtpError> {
+    database.entries.into_iter().map(|entry| {
+        let kind = match (entry.entry_type.as_str(), entry.info.period, entry.info.counter) {
+            ("totp", Some(period), _) if period > 0 => OtpUriKind::Totp { period },
+            ("hotp", _, Some(counter)) => OtpUriKind::Hotp { counter },
+            _ => {
+                return Err(OtpError::InvalidVault);
+            }
+        };
+
+        let algorithm = entry.info.algo.parse()?;
+        let mut secret = entry.info.secret.to_ascii_uppercase();
+        while !secret.len().is_multiple_of(8) {
+            secret.push('=');
+        }
+        let secret = HOTP::decode_base32(&secret)?;
+        if secret.is_empty() {
+            return Err(OtpError::EmptySecret);
+        }
+
+        Ok(OtpUri {
+            kind,
+            secret: HOTP {
+                secret,
+                algorithm,
+            },
+            account: entry.name,
+            issuer: Some(entry.issuer).filter(|issuer| !issuer.is_empty()),
+            digits: Digits::try_new(entry.info.digits).map_err(|_| OtpError::InvalidVault)?,
+            image: None,
+        })
+    }).collect()
+}
+
+/// Derives the key of a password slot with scrypt and decrypts the master key with it.
+///
+/// Returns `None` if the password does not open this slot, and `OtpError::InvalidVault` if the scrypt parameters
+/// exceed `SCRYPT_MAXIMUM_N`, `SCRYPT_MAXIMUM_R` or `SCRYPT_MAXIMUM_P`, as they come from an untrusted file.
+fn open_password_slot(slot: &Slot, password: &str) -> Result<Option<Vec<u8>>, OtpError> {
+    let (n, r, p, salt) = match (slot.n, slot.r, slot.p, &slot.salt) {
+        (Some(n), Some(r), Some(p), Some(salt))
+            if n.is_power_of_two() && n <= SCRYPT_MAXIMUM_N && r <= SCRYPT_MAXIMUM_R && p <= SCRYPT_MAXIMUM_P =>
+        {
+            (n, r, p, decode_hex(salt)?)
+        },
+        _ => {
+            return Err(OtpError::InvalidVault);
+        }
+    };
+
+    let params = match scrypt::Params::new(n.trailing_zeros() as u8, r, p, 32) {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(OtpError::InvalidVault);
+        }
+    };
+    let mut derived = [0u8; 32];
+    if scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived).is_err() {
+        return Err(OtpError::KeyDerivation);
+    }
+
+    match decrypt(&derived, &slot.key_params, decode_hex(&slot.key)?) {
+        Ok(key) => Ok(Some(key)),
+        Err(OtpError::VaultDecryption) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Decrypts AES-256-GCM `ciphertext`, with the nonce and tag stored next to it as hex.
+fn decrypt(key: &[u8], params: &KeyParams, mut ciphertext: Vec<u8>) -> Result<Vec<u8>, OtpError> {
+    use ring::aead;
+
+    let nonce = match aead::Nonce::try_assume_unique_for_key(&decode_hex(&params.nonce)?) {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(OtpError::InvalidVault);
+        }
+    };
+    let key = match aead::UnboundKey::new(&aead::AES_256_GCM, key) {
+        Ok(v) => aead::LessSafeKey::new(v),
+        Err(_) => {
+            return Err(OtpError::InvalidVault);
+        }
+    };
+
+    ciphertext.extend_from_slice(&decode_hex(&params.tag)?);
+    match key.open_in_place(nonce, aead::Aad::empty(), &mut ciphertext) {
+        Ok(plaintext) => Ok(Vec::from(&*plaintext)),
+        Err(_) => Err(OtpError::VaultDecryption),
+    }
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>, OtpError> {
+    let mut buffer = vec![0u8; data.len() / 2];
+    match binascii::hex2bin(data.as_bytes(), &mut buffer) {
+        Ok(v) => Ok(Vec::from(v)),
+        Err(_) => Err(OtpError::InvalidHex),
+    }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, OtpError> {
+    let mut buffer = vec![0u8; data.len() / 4 * 3];
+    match binascii::b64decode(data.as_bytes(), &mut buffer) {
+        Ok(v) => Ok(Vec::from(v)),
+        Err(_) => Err(OtpError::InvalidBase64),
+    }
+}
+
+/// Generates a random version 4 UUID, as Aegis identifies entries by UUID.
+fn random_uuid() -> Result<String, OtpError> {
+    let mut bytes = match HOTP::generate_secret(16) {
+        Ok(v) => v,
+        Err(_) => {
+            return Err(OtpError::RngError);
+        }
+    };
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32]))
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+/// Builds a `TOTP`, starting from the RFC6238 recommended 30 seconds time step and a T0 of 0.
+///
+/// # Example Usage
+/// ```
+/// use libotp::{HOTP, TOTP};
+///
+/// let totp = TOTP::builder()
+///     .secret(HOTP::from_base32("VMNW2EC7X3OCJHITBVSVZW5MVCUIL5SR").unwrap())
+///     .issuer("Example Co")
+///     .account_name("alice@example.com")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(totp.issuer(), Some("Example Co"));
+/// ```
+pub struct TOTPBuilder {
+    secret: Option<HOTP>,
+    time_step: u64,
+    start_time: u64,
+    issuer: Option<String>,
+    account_name: Option<String>,
+}
+
+impl Default for TOTPBuilder {
+    fn default() -> TOTPBuilder {
+        TOTPBuilder {
+            secret: None,
+            time_step: DEFAULT_TIME_STEP,
+            start_time: 0,
+            issuer: None,
+            account_name: None,
+        }
+    }
+}
+
+impl TOTPBuilder {
+    /// Creates a new builder with the default configuration.
+    pub fn new() -> TOTPBuilder {
+        TOTPBuilder::default()
+    }
+
+    /// Sets the HOTP secret to use for TOTP generation, this is required.
+    pub fn secret(mut self, s: HOTP) -> Self {
+        self.secret = Some(s);
+        self
+    }
+
+    /// Sets the time frame to allow every password, in seconds.
+    pub fn time_step(mut self, step: u64) -> Self {
+        self.time_step = step;
+        self
+    }
+
+    /// Sets T0, the Unix time from which time steps are counted.
+    pub fn start_time(mut self, t: u64) -> Self {
+        self.start_time = t;
+        self
+    }
+
+    /// Sets the provider or service the TOTP belongs to.
+    pub fn issuer(mut self, issuer: &str) -> Self {
+        self.issuer = Some(String::from(issuer));
+        self
+    }
+
+    /// Sets the name of the account the TOTP belongs to.
+    pub fn account_name(mut self, name: &str) -> Self {
+        self.account_name = Some(String::from(name));
+        self
+    }
+
+    /// Validates the configuration and creates the `TOTP`.
+    pub fn build(self) -> Result<TOTP, OtpError> {
+        let secret = self.secret.ok_or(OtpError::MissingSecret)?;
+        let mut totp = TOTP::new(secret, TimeStep::new(self.time_step)?, self.start_time);
+        totp.issuer = self.issuer;
+        totp.account_name = self.account_name;
+        Ok(totp)
+    }
+}
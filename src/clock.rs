@@ -0,0 +1,37 @@
+use super::OtpError;
+
+/// A source of the current time for `TOTP`.
+pub trait TimeProvider: Send + Sync {
+    /// Returns the current Unix time in seconds, or `OtpError::ClockError` if the clock can't be read.
+    fn unix_seconds(&self) -> Result<u64, OtpError>;
+}
+
+/// Reads the current time from the system clock.
+///
+/// Implements `TimeProvider` only when the `std` feature is enabled.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemTimeClock;
+
+#[cfg(feature = "std")]
+impl TimeProvider for SystemTimeClock {
+    fn unix_seconds(&self) -> Result<u64, OtpError> {
+        match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(now) => Ok(now.as_secs()),
+            Err(_) => Err(OtpError::ClockError),
+        }
+    }
+}
+
+/// A clock that is stuck at the given Unix time, in seconds.
+///
+/// Useful for verifying known test vectors, such as the ones in RFC6238 appendix B.
+#[cfg(any(test, feature = "testing"))]
+#[derive(Debug, Copy, Clone)]
+pub struct FixedClock(pub u64);
+
+#[cfg(any(test, feature = "testing"))]
+impl TimeProvider for FixedClock {
+    fn unix_seconds(&self) -> Result<u64, OtpError> {
+        Ok(self.0)
+    }
+}
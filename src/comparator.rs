@@ -0,0 +1,46 @@
+use super::*;
+
+/// Precomputed TOTP codes of a window of time frames, compared in constant time.
+///
+/// Verifying a window in a loop that stops at the first match leaks which time frame matched through
+/// the time taken. `compare` always checks every candidate, so the time only depends on the window size.
+#[derive(Clone, Debug)]
+pub struct OtpComparator {
+    attempts: Vec<(i32, u32)>,
+}
+
+impl OtpComparator {
+    /// Computes the codes of the current time frame and the window around it.
+    ///
+    /// # Arguments
+    /// * `totp` - The TOTP to verify codes with.
+    /// * `digits` - The amount of digits set for the OTP.
+    /// * `window` - Amount of frames to accept before and after the current one (0=Only current, 1=Previous+Now+Next, etc...)
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `Digits::try_new`.
+    pub fn build<T: TimeProvider>(totp: &TOTP<T>, digits: u32, window: u32) -> OtpComparator {
+        OtpComparator {
+            attempts: totp.get_otp_range(digits, -(window as i32), window as i32),
+        }
+    }
+
+    /// Compares a code against every precomputed code, returning the offset of the time frame it matched.
+    ///
+    /// If several time frames share the same code, the offset closest to the current time frame is returned.
+    ///
+    /// # Arguments
+    /// * `presented` - The user provided code to verify.
+    pub fn compare(&self, presented: u32) -> Option<i32> {
+        let mut result = None;
+
+        for &(offset, code) in &self.attempts {
+            let is_closer = result.is_none_or(|matched: i32| offset.abs() < matched.abs());
+            if otp_eq(code, presented) & is_closer {
+                result = Some(offset);
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,85 @@
+//! Compact binary encoding of a TOTP configuration, for environments without a serialization framework.
+//!
+//! The layout is the format version, the magic `TOTP`, the algorithm, the time step and start time as 8 byte
+//! little endian integers, and the secret prefixed by its length as a 4 byte little endian integer.
+//! The issuer and account name are not included.
+
+use super::*;
+
+/// Marks the start of a serialized configuration.
+const MAGIC: &[u8; 4] = b"TOTP";
+
+/// Version of the format written by `TOTP::serialize_config`.
+const FORMAT_VERSION: u8 = 1;
+
+/// Length of everything before the secret bytes.
+const HEADER_LEN: usize = 1 + MAGIC.len() + 1 + 8 + 8 + 4;
+
+fn algorithm_to_byte(algorithm: HOTPAlgorithm) -> u8 {
+    match algorithm {
+        HOTPAlgorithm::HMACSHA1 => 1,
+        HOTPAlgorithm::HMACSHA256 => 2,
+        HOTPAlgorithm::HMACSHA512 => 3,
+        HOTPAlgorithm::HMACSHA3_256 => 4,
+        HOTPAlgorithm::HMACSHA3_512 => 5,
+    }
+}
+
+#[cfg(feature = "std")]
+fn algorithm_from_byte(value: u8) -> Result<HOTPAlgorithm, OtpError> {
+    match value {
+        1 => Ok(HOTPAlgorithm::HMACSHA1),
+        2 => Ok(HOTPAlgorithm::HMACSHA256),
+        3 => Ok(HOTPAlgorithm::HMACSHA512),
+        4 => Ok(HOTPAlgorithm::HMACSHA3_256),
+        5 => Ok(HOTPAlgorithm::HMACSHA3_512),
+        _ => Err(OtpError::UnknownAlgorithm),
+    }
+}
+
+impl<T> TOTP<T> {
+    /// Serializes the secret, algorithm, time step and start time into a compact binary format.
+    ///
+    /// Load it back with `TOTP::deserialize_config`. The output contains the raw secret and must be stored as securely.
+    pub fn serialize_config(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(HEADER_LEN + self.secret.secret.len());
+        data.push(FORMAT_VERSION);
+        data.extend_from_slice(MAGIC);
+        data.push(algorithm_to_byte(self.secret.algorithm));
+        data.extend_from_slice(&self.time_step.to_le_bytes());
+        data.extend_from_slice(&self.start_time.to_le_bytes());
+        data.extend_from_slice(&(self.secret.secret.len() as u32).to_le_bytes());
+        data.extend_from_slice(&self.secret.secret);
+        data
+    }
+}
+
+#[cfg(feature = "std")]
+impl TOTP {
+    /// Loads a configuration written by `serialize_config`.
+    ///
+    /// Returns `OtpError::InvalidConfig` if the version or the magic don't match, or the length is wrong, and
+    /// `OtpError::SecretTooShort` if the secret is shorter than 16 bytes, as for every other import. Short secrets
+    /// created with `HOTP::from_bin_unchecked` can be serialized, but not loaded back.
+    ///
+    /// # Arguments
+    /// * `data` - The serialized configuration.
+    pub fn deserialize_config(data: &[u8]) -> Result<TOTP, OtpError> {
+        if data.len() < HEADER_LEN || data[0] != FORMAT_VERSION || &data[1..1 + MAGIC.len()] != MAGIC {
+            return Err(OtpError::InvalidConfig);
+        }
+
+        let algorithm = algorithm_from_byte(data[5])?;
+        let time_step = u64::from_le_bytes(data[6..14].try_into().expect("slice is 8 bytes long"));
+        let start_time = u64::from_le_bytes(data[14..22].try_into().expect("slice is 8 bytes long"));
+        let secret_len = u32::from_le_bytes(data[22..26].try_into().expect("slice is 4 bytes long")) as usize;
+
+        let secret = &data[HEADER_LEN..];
+        if secret.len() != secret_len {
+            return Err(OtpError::InvalidConfig);
+        }
+
+        let secret = HOTP::with_valid_length(Vec::from(secret), algorithm)?;
+        Ok(TOTP::new(secret, TimeStep::new(time_step)?, start_time))
+    }
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+/// A `TOTP` that always verifies codes against a stored window of time frames.
+///
+/// Storing the tolerance avoids mixing windowed and unwindowed verification across a code base.
+/// The last accepted time frame is remembered, so a code can only be used once.
+#[derive(Clone, Debug)]
+pub struct DriftTolerantTOTP<T = SystemTimeClock> {
+    inner: TOTP<T>,
+    window: u32,
+    last_counter: Option<u64>,
+}
+
+impl<T: TimeProvider> DriftTolerantTOTP<T> {
+    /// Creates a new DriftTolerantTOTP.
+    ///
+    /// # Arguments
+    /// * `totp` - The TOTP to verify codes with.
+    /// * `window` - Amount of frames to accept before and after the current one (0=Only current, 1=Previous+Now+Next, etc...)
+    pub fn new(totp: TOTP<T>, window: u32) -> DriftTolerantTOTP<T> {
+        DriftTolerantTOTP {
+            inner: totp,
+            window,
+            last_counter: None,
+        }
+    }
+
+    /// Verifies the given OTP against the current time frame and the stored window around it.
+    ///
+    /// Codes from the last accepted time frame or before it are reported as `OtpValidationResult::ReplayDetected`.
+    ///
+    /// # Arguments
+    /// * `code` - The user provided code to verify.
+    /// * `digits` - The amount of digits set for the OTP.
+    pub fn verify(&mut self, code: u32, digits: u32) -> OtpValidationResult {
+        let now = match self.inner.clock.unix_seconds() {
+            Ok(now) => now,
+            Err(err) => {
+                return OtpValidationResult::SecretError(err);
+            }
+        };
+        if let Err(err) = Digits::try_new(digits) {
+            return OtpValidationResult::SecretError(err);
+        }
+
+        let counter = self.inner.counter_at(now);
+        let window_offset = match self.inner.verify_with_window_at_counter(code, digits, self.window, counter) {
+            TotpVerificationResult::Accepted { window_offset } => window_offset,
+            TotpVerificationResult::Rejected => {
+                return OtpValidationResult::InvalidCode;
+            }
+        };
+
+        let counter_value = ((counter as i64) + (window_offset as i64)) as u64;
+        if self.last_counter.is_some_and(|last| counter_value <= last) {
+            return OtpValidationResult::ReplayDetected;
+        }
+        self.last_counter = Some(counter_value);
+
+        OtpValidationResult::Valid { window_offset, counter_value }
+    }
+
+    /// Verifies the given OTP like `verify`, keeping used codes in `rp` instead of this TOTP.
+    ///
+    /// Codes already recorded in `rp` are rejected, accepted codes are recorded, and codes of time frames that
+    /// fell out of the window are evicted. Clock errors and invalid digits reject the code.
+    ///
+    /// # Arguments
+    /// * `code` - The user provided code to verify.
+    /// * `digits` - The amount of digits set for the OTP.
+    /// * `rp` - The store of used codes.
+    pub fn verify_with_replay_protection<R: ReplayProtection>(&self, code: u32, digits: u32, rp: &mut R) -> TotpVerificationResult {
+        let now = match self.inner.clock.unix_seconds() {
+            Ok(now) => now,
+            Err(_) => {
+                return TotpVerificationResult::Rejected;
+            }
+        };
+        if Digits::try_new(digits).is_err() {
+            return TotpVerificationResult::Rejected;
+        }
+
+        let counter = self.inner.counter_at(now);
+        let result = self.inner.verify_with_window_at_counter(code, digits, self.window, counter);
+        if let TotpVerificationResult::Accepted { window_offset } = result {
+            let counter_value = ((counter as i64) + (window_offset as i64)) as u64;
+            if rp.is_replay(counter_value, code) {
+                return TotpVerificationResult::Rejected;
+            }
+            rp.record_use(counter_value, code);
+            rp.evict_old(counter.saturating_sub(self.window as u64));
+        }
+        result
+    }
+
+    /// Returns the amount of frames accepted before and after the current one.
+    pub fn tolerance(&self) -> u32 {
+        self.window
+    }
+
+    /// Changes the amount of frames accepted before and after the current one.
+    ///
+    /// # Arguments
+    /// * `window` - Amount of frames to accept before and after the current one.
+    pub fn set_tolerance(&mut self, window: u32) {
+        self.window = window;
+    }
+
+    /// Returns the wrapped TOTP.
+    pub fn totp(&self) -> &TOTP<T> {
+        &self.inner
+    }
+}
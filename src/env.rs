@@ -0,0 +1,53 @@
+use super::*;
+
+impl HOTP {
+    /// Loads a secret from the environment variable `var_name`, detecting whether it is hex or base32 encoded.
+    ///
+    /// The encoding is detected by the rules of `HOTP::from_hex_or_base32`, base32 may be upper or lower case. Use
+    /// `from_env_with_format` when the value is ambiguous, e.g. a base32 secret only made of hex digits.
+    ///
+    /// Returns `OtpError::EnvVarMissing` if the variable is not set or not valid unicode, `OtpError::AmbiguousEncoding`
+    /// if the value could be both hex and base32, and `OtpError::InvalidEncoding` if the value can't be decoded.
+    ///
+    /// # Arguments
+    /// * `var_name` - Name of the environment variable.
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    pub fn from_env(var_name: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        HOTP::from_env_with_format(var_name, algorithm, SecretFormat::Auto)
+    }
+
+    /// Loads a secret with a known encoding from the environment variable `var_name`, `SecretFormat::Auto` detects it.
+    ///
+    /// Returns `OtpError::EnvVarMissing` if the variable is not set or not valid unicode, and
+    /// `OtpError::InvalidEncoding` if the value can't be decoded.
+    ///
+    /// # Arguments
+    /// * `var_name` - Name of the environment variable.
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    /// * `format` - Encoding of the secret.
+    pub fn from_env_with_format(var_name: &str, algorithm: HOTPAlgorithm, format: SecretFormat) -> Result<HOTP, OtpError> {
+        let value = HOTP::read_env(var_name)?;
+        HOTP::decode_env(&value, algorithm, format)
+    }
+
+    fn read_env(var_name: &str) -> Result<String, OtpError> {
+        match std::env::var(var_name) {
+            Ok(value) => Ok(String::from(value.trim())),
+            Err(_) => Err(OtpError::EnvVarMissing),
+        }
+    }
+
+    fn decode_env(value: &str, algorithm: HOTPAlgorithm, format: SecretFormat) -> Result<HOTP, OtpError> {
+        let result = match format {
+            SecretFormat::Base64 => HOTP::from_base64(value, algorithm),
+            format => HOTP::from_hex_or_base32_with_hint(&value.to_ascii_uppercase(), algorithm, format),
+        };
+
+        match result {
+            Err(OtpError::InvalidBase32 | OtpError::InvalidHex | OtpError::InvalidBase64 | OtpError::InvalidBase64Padding) => {
+                Err(OtpError::InvalidEncoding)
+            },
+            result => result,
+        }
+    }
+}
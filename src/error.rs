@@ -0,0 +1,128 @@
+use core::fmt;
+
+/// Errors returned by the fallible operations of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtpError {
+    /// The provided string is not valid RFC4648 base32, or Crockford base32 with a matching check symbol.
+    InvalidBase32,
+    /// The provided string is not valid hex, or has an odd length.
+    InvalidHex,
+    /// No HMAC algorithm matches the provided secret.
+    UnknownAlgorithm,
+    /// The provided string contains characters that are not valid base64.
+    InvalidBase64,
+    /// The provided base64 string has incorrect padding or length.
+    InvalidBase64Padding,
+    /// The provided secret is empty.
+    EmptySecret,
+    /// The provided string is not a valid `otpauth://` URI.
+    InvalidUri,
+    /// The secret is shorter than the `minimum` amount of bytes.
+    SecretTooShort { actual: usize, minimum: usize },
+    /// The secret is longer than the `maximum` amount of bytes.
+    SecretTooLong { actual: usize, maximum: usize },
+    /// The OS random number generator failed.
+    RngError,
+    /// The current time could not be read, or is before the TOTP's start time.
+    ClockError,
+    /// The OTP length is not between 1 and 10 digits.
+    InvalidDigits,
+    /// The time step is zero.
+    InvalidTimeStep,
+    /// No secret was provided.
+    MissingSecret,
+    /// The time frame offset points before the first time frame.
+    InvalidOffset,
+    /// The key derivation iteration count is zero.
+    InvalidIterations,
+    /// The key derivation parameters or salt were rejected.
+    KeyDerivation,
+    /// No counter within the look-ahead window matched the presented OTP.
+    SyncFailed,
+    /// The Google Authenticator migration payload is malformed.
+    InvalidMigrationPayload,
+    /// The authenticator vault is malformed or uses unsupported entries.
+    InvalidVault,
+    /// No key slot of the encrypted vault could be opened, the password may be wrong.
+    VaultDecryption,
+    /// The text secret contains non ASCII characters.
+    InvalidAscii,
+    /// The environment variable holding the secret is not set.
+    EnvVarMissing,
+    /// The secret could not be decoded with the expected encoding.
+    InvalidEncoding,
+    /// The UUID is neither hyphenated nor 32 hex digits.
+    InvalidUuid,
+    /// The secret is both valid hex and valid base32, so its encoding can't be detected.
+    AmbiguousEncoding,
+    /// Too few dice throws were provided to reach 128 bits of entropy.
+    InsufficientEntropy,
+    /// A dice throw is outside of the die's sides, or the die has less than 2 sides.
+    InvalidDiceThrow,
+    /// The provided string is not valid base58check, or its checksum doesn't match.
+    InvalidBase58,
+    /// The serialized TOTP configuration is malformed or uses an unsupported version.
+    InvalidConfig,
+    /// The PBKDF2 iteration count is below the accepted minimum.
+    WeakIterationCount,
+    /// The secret sharing threshold is 0 or greater than the amount of shares.
+    InvalidThreshold,
+    /// The secret share is malformed, or doesn't belong to the same split as the other shares.
+    InvalidShare,
+    /// Fewer distinct secret shares than the threshold were provided.
+    InsufficientShares,
+    /// The algorithm of the secret differs from the one required by the OTP policy.
+    AlgorithmMismatch,
+    /// The HOTP counter reached `u64::MAX` and can't be advanced anymore.
+    CounterExhausted,
+}
+
+impl fmt::Display for OtpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OtpError::InvalidBase32 => write!(f, "invalid base32 encoded secret"),
+            OtpError::InvalidHex => write!(f, "invalid hex encoded secret"),
+            OtpError::UnknownAlgorithm => write!(f, "unable to determine HMAC algorithm"),
+            OtpError::InvalidBase64 => write!(f, "invalid base64 encoded secret"),
+            OtpError::InvalidBase64Padding => write!(f, "invalid base64 padding"),
+            OtpError::EmptySecret => write!(f, "secret is empty"),
+            OtpError::InvalidUri => write!(f, "invalid otpauth URI"),
+            OtpError::SecretTooShort { actual, minimum } => {
+                write!(f, "secret is {} bytes long, at least {} bytes are required", actual, minimum)
+            },
+            OtpError::SecretTooLong { actual, maximum } => {
+                write!(f, "secret is {} bytes long, at most {} bytes are allowed", actual, maximum)
+            },
+            OtpError::RngError => write!(f, "failed to generate random secret"),
+            OtpError::ClockError => write!(f, "failed to read the current time"),
+            OtpError::InvalidDigits => write!(f, "OTP length must be between 1 and 10 digits"),
+            OtpError::InvalidTimeStep => write!(f, "time step must be greater than 0"),
+            OtpError::MissingSecret => write!(f, "no secret was provided"),
+            OtpError::InvalidOffset => write!(f, "offset points before the first time frame"),
+            OtpError::InvalidIterations => write!(f, "iteration count must be greater than 0"),
+            OtpError::KeyDerivation => write!(f, "invalid key derivation parameters"),
+            OtpError::SyncFailed => write!(f, "no counter in the look-ahead window matched the OTP"),
+            OtpError::InvalidMigrationPayload => write!(f, "invalid Google Authenticator migration payload"),
+            OtpError::InvalidVault => write!(f, "invalid or unsupported authenticator vault"),
+            OtpError::VaultDecryption => write!(f, "failed to decrypt the vault"),
+            OtpError::InvalidAscii => write!(f, "secret contains non ascii characters"),
+            OtpError::EnvVarMissing => write!(f, "environment variable is not set"),
+            OtpError::InvalidEncoding => write!(f, "invalid secret encoding"),
+            OtpError::InvalidUuid => write!(f, "invalid uuid"),
+            OtpError::AmbiguousEncoding => write!(f, "secret encoding is ambiguous, could be hex or base32"),
+            OtpError::InsufficientEntropy => write!(f, "not enough dice throws for 128 bits of entropy"),
+            OtpError::InvalidDiceThrow => write!(f, "invalid dice throw"),
+            OtpError::InvalidBase58 => write!(f, "invalid base58check encoded secret"),
+            OtpError::InvalidConfig => write!(f, "invalid or unsupported TOTP configuration"),
+            OtpError::WeakIterationCount => write!(f, "iteration count is too low"),
+            OtpError::InvalidThreshold => write!(f, "threshold must be between 1 and the amount of shares"),
+            OtpError::InvalidShare => write!(f, "invalid secret share"),
+            OtpError::InsufficientShares => write!(f, "not enough secret shares to recombine the secret"),
+            OtpError::AlgorithmMismatch => write!(f, "secret algorithm does not match the OTP policy"),
+            OtpError::CounterExhausted => write!(f, "HOTP counter is exhausted"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OtpError {}
@@ -0,0 +1,54 @@
+use super::*;
+
+/// Cost parameters for Argon2id secret derivation.
+///
+/// The defaults follow the OWASP recommendation of 19 MiB of memory, 2 iterations and 1 degree of parallelism.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Argon2Params {
+    /// Memory size in KiB.
+    pub m_cost: u32,
+    /// Number of iterations.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Argon2Params {
+        Argon2Params {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+impl HOTP {
+    /// Derives a deterministic secret from a password and salt using the memory-hard Argon2id.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to derive the secret from.
+    /// * `salt` - Salt for the key derivation, at least 8 bytes and unique per account.
+    /// * `params` - Argon2id cost parameters, `Argon2Params::default()` follows the OWASP recommendation.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_from_argon2(password: &[u8], salt: &[u8], params: Argon2Params, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let params = match argon2::Params::new(params.m_cost, params.t_cost, params.p_cost, Some(algorithm.output_len())) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(OtpError::KeyDerivation);
+            }
+        };
+        let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+        let mut secret = vec![0u8; algorithm.output_len()];
+        if argon2.hash_password_into(password, salt, &mut secret).is_err() {
+            return Err(OtpError::KeyDerivation);
+        }
+
+        Ok(HOTP {
+            secret,
+            algorithm,
+        })
+    }
+}
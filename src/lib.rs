@@ -1,3 +1,4 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 //! HMAC and Time-Based One-Time-Password implementations based on RFC4226 and RFC6238.
 //!
@@ -29,18 +30,253 @@
 //!     totp(secret, OTP_DIGITS, TOTP_STEP, 0)
 //! }
 //! ```
+//!
+//! # Loading secrets
+//! * `HOTP::from_base32`, `HOTP::from_hex` and `HOTP::from_base64` decode encoded secrets, as used by otpauth URIs.
+//! * `HOTP::from_ascii` uses a plain text key as is, as stored by many enterprise token systems.
+//! * `HOTP::from_bin` takes the raw key bytes.
+//!
+//! # Features
+//! * `std` (default) - Enables the system clock, and everything that reads the current time through it.
+//!   Without it the crate is `no_std` and only requires `alloc`, `TOTP::new_with_clock` accepts a custom clock.
+//! * `zeroize` (default) - Wipes secrets from memory when they are dropped.
+//! * `serde` - Serialization support, and JSON import and export of `OtpVault`.
+//! * `argon2` - Argon2id secret derivation with `HOTP::new_from_argon2`.
+//! * `aegis` - Import and export of Aegis Authenticator vaults, such as `HOTP::from_aegis_json`.
+//! * `base58` - base58check encoded secrets with `HOTP::from_base58` and `HOTP::get_secret_base58`.
+//! * `shamir` - Splitting secrets between custodians with `HOTP::split_threshold`, requires `std`.
+//! * `testing` - Exposes `FixedClock` and `HOTP::new_insecure_zeroed` for deterministic tests.
+//! * `test-vectors` - Exposes the RFC4226 and RFC6238 test secrets, such as `HOTP::rfc4226_test_secret`.
 
+extern crate alloc;
 extern crate ring;
 extern crate binascii;
+extern crate subtle;
+extern crate hmac;
+extern crate sha3;
+extern crate rand_core;
+#[cfg(feature = "zeroize")] extern crate zeroize;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(feature = "argon2")] extern crate argon2;
+#[cfg(feature = "serde")] extern crate serde_json;
+#[cfg(feature = "aegis")] extern crate scrypt;
+#[cfg(feature = "base58")] extern crate bs58;
+#[cfg(feature = "shamir")] extern crate sharks;
 
-#[cfg(test)] mod tests;
+#[cfg(all(test, feature = "std"))] mod tests;
+#[cfg(feature = "aegis")] mod aegis;
+#[cfg(feature = "std")] mod builder;
+mod clock;
+mod comparator;
+mod config;
+mod drift;
+#[cfg(feature = "std")] mod env;
+mod error;
+#[cfg(feature = "argon2")] mod kdf;
+mod migration;
+mod recovery;
+mod replay;
+#[cfg(feature = "serde")] mod serialization;
+#[cfg(feature = "shamir")] mod shamir;
+mod steam;
+#[cfg(any(test, feature = "test-vectors"))] mod test_vectors;
+mod uri;
 mod utils;
+mod vault;
+
+#[cfg(feature = "std")] pub use builder::TOTPBuilder;
+pub use clock::{TimeProvider, SystemTimeClock};
+#[cfg(any(test, feature = "testing"))] pub use clock::FixedClock;
+pub use comparator::OtpComparator;
+pub use drift::DriftTolerantTOTP;
+pub use error::OtpError;
+#[cfg(feature = "argon2")] pub use kdf::Argon2Params;
+pub use recovery::RecoveryCode;
+pub use replay::{MemoryReplayProtection, ReplayProtection, UsedOtpCache};
+#[cfg(feature = "shamir")] pub use shamir::SecretShare;
+pub use steam::SteamGuardTotp;
+pub use uri::{OtpUri, OtpUriKind, OtpUriMeta};
+pub use vault::{OtpEntry, OtpVault};
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Time step in seconds recommended by RFC6238 section 5.2.
+pub const DEFAULT_TIME_STEP: u64 = 30;
+
+/// OTP length in digits used by most authenticator apps, and the minimum recommended by RFC4226.
+pub const DEFAULT_DIGITS: u32 = 6;
+
+/// Minimum length of a shared secret in bytes, as required by RFC4226 section 4.
+pub const RFC_MINIMUM_SECRET_BYTES: usize = 16;
+
+/// Maximum length of a generated secret in bytes.
+const MAXIMUM_SECRET_BYTES: usize = 1024;
+
+/// A secret length in bytes, between the RFC4226 minimum of 16 and 1024 bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SecretLength(usize);
+
+impl SecretLength {
+    /// The HMAC-SHA1 output length, 20 bytes.
+    pub const SHA1: SecretLength = SecretLength(20);
+    /// The HMAC-SHA256 output length, 32 bytes.
+    pub const SHA256: SecretLength = SecretLength(32);
+    /// The HMAC-SHA512 output length, 64 bytes.
+    pub const SHA512: SecretLength = SecretLength(64);
+
+    /// Validates a secret length.
+    ///
+    /// # Arguments
+    /// * `n` - Length in bytes, between 16 and 1024.
+    pub fn try_new(n: usize) -> Result<SecretLength, OtpError> {
+        if n < RFC_MINIMUM_SECRET_BYTES {
+            return Err(OtpError::SecretTooShort { actual: n, minimum: RFC_MINIMUM_SECRET_BYTES });
+        }
+        if n > MAXIMUM_SECRET_BYTES {
+            return Err(OtpError::SecretTooLong { actual: n, maximum: MAXIMUM_SECRET_BYTES });
+        }
+        Ok(SecretLength(n))
+    }
+
+    /// Returns the length in bytes.
+    pub fn get(self) -> usize {
+        self.0
+    }
+}
+
+/// A TOTP time step in seconds, which is never zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimeStep(core::num::NonZeroU64);
+
+impl TimeStep {
+    /// The RFC6238 recommended time step of 30 seconds.
+    pub const THIRTY_SECONDS: TimeStep = TimeStep::from_const(30);
+    /// A time step of 60 seconds.
+    pub const SIXTY_SECONDS: TimeStep = TimeStep::from_const(60);
+
+    /// Validates a time step.
+    ///
+    /// # Arguments
+    /// * `n` - The time step in seconds, greater than 0.
+    pub fn new(n: u64) -> Result<TimeStep, OtpError> {
+        match core::num::NonZeroU64::new(n) {
+            Some(v) => Ok(TimeStep(v)),
+            None => Err(OtpError::InvalidTimeStep),
+        }
+    }
+
+    /// Returns the time step in seconds.
+    pub fn get(self) -> u64 {
+        self.0.get()
+    }
+
+    const fn from_const(n: u64) -> TimeStep {
+        match core::num::NonZeroU64::new(n) {
+            Some(v) => TimeStep(v),
+            None => panic!("time step must be greater than 0"),
+        }
+    }
+}
 
-#[derive(Copy, Clone)]
+/// An OTP length in digits, between 1 and 10.
+///
+/// RFC4226 requires at least 6 digits, shorter codes are accepted for compatibility but are easier to guess.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Digits(u32);
+
+impl Digits {
+    /// 6 digits, the RFC4226 minimum and the most common length.
+    pub const SIX: Digits = Digits(6);
+    /// 7 digits.
+    pub const SEVEN: Digits = Digits(7);
+    /// 8 digits, as used by the RFC6238 test vectors.
+    pub const EIGHT: Digits = Digits(8);
+
+    /// Validates an OTP length.
+    ///
+    /// # Arguments
+    /// * `n` - OTP length in digits, between 1 and 10.
+    pub fn try_new(n: u32) -> Result<Digits, OtpError> {
+        if !(1..=10).contains(&n) {
+            return Err(OtpError::InvalidDigits);
+        }
+        Ok(Digits(n))
+    }
+
+    /// Returns the OTP length in digits.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Validates an OTP length for the `u32` based methods, which panic on invalid lengths.
+    fn expect_valid(n: u32) -> Digits {
+        match Digits::try_new(n) {
+            Ok(digits) => digits,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// The OTP parameters that authenticators and servers have to agree on, besides the secret.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OtpPolicy {
+    /// The HMAC algorithm.
+    pub algorithm: HOTPAlgorithm,
+    /// OTP length in digits.
+    pub digits: Digits,
+    /// The time frame of every TOTP.
+    pub time_step: TimeStep,
+}
+
+impl OtpPolicy {
+    /// The RFC recommended policy, supported by every authenticator app: SHA1, 6 digits and a 30 seconds time step.
+    pub fn new_standard() -> OtpPolicy {
+        OtpPolicy {
+            algorithm: HOTPAlgorithm::HMACSHA1,
+            digits: Digits::SIX,
+            time_step: TimeStep::THIRTY_SECONDS,
+        }
+    }
+
+    /// A stronger policy: SHA256, 8 digits and a 30 seconds time step. Not every authenticator app supports it.
+    pub fn new_high_security() -> OtpPolicy {
+        OtpPolicy {
+            algorithm: HOTPAlgorithm::HMACSHA256,
+            digits: Digits::EIGHT,
+            time_step: TimeStep::THIRTY_SECONDS,
+        }
+    }
+}
+
+impl Default for OtpPolicy {
+    fn default() -> OtpPolicy {
+        OtpPolicy::new_standard()
+    }
+}
+
+/// PBKDF2-HMAC-SHA256 iteration count recommended by current NIST guidance.
+pub const PBKDF2_DEFAULT_ITERATIONS: u32 = 600_000;
+
+/// Lowest PBKDF2-HMAC-SHA256 iteration count accepted by `HOTP::new_from_password` unless forced.
+pub const PBKDF2_MINIMUM_ITERATIONS: u32 = 100_000;
+
+/// The HMAC algorithm used to generate OTPs.
+///
+/// New algorithms may be added in minor releases, so matches on it outside of this crate need a wildcard arm.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum HOTPAlgorithm {
+    /// The RFC4226 baseline algorithm, used when none is specified.
+    #[default]
     HMACSHA1,
     HMACSHA256,
     HMACSHA512,
+    HMACSHA3_256,
+    HMACSHA3_512,
 }
 
 impl HOTPAlgorithm {
@@ -61,19 +297,100 @@ impl HOTPAlgorithm {
         }
     }
 
+    /// Returns the matching `ring` HMAC algorithm.
+    ///
+    /// # Panics
+    /// Panics for the SHA3 algorithms, which `ring` does not implement, see `ring_algorithm`.
+    #[deprecated(note = "use `ring_algorithm`, which returns `None` for the SHA3 algorithms")]
     pub fn get_algorithm(&self) -> ring::hmac::Algorithm {
+        self.ring_algorithm().expect("ring does not implement SHA3")
+    }
+
+    /// Returns the matching `ring` HMAC algorithm, `None` for the SHA3 algorithms which `ring` does not implement.
+    pub fn ring_algorithm(&self) -> Option<ring::hmac::Algorithm> {
         match *self {
-            HOTPAlgorithm::HMACSHA1 => ring::hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
-            HOTPAlgorithm::HMACSHA256 => ring::hmac::HMAC_SHA256,
-            HOTPAlgorithm::HMACSHA512 => ring::hmac::HMAC_SHA512,
+            HOTPAlgorithm::HMACSHA1 => Some(ring::hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY),
+            HOTPAlgorithm::HMACSHA256 => Some(ring::hmac::HMAC_SHA256),
+            HOTPAlgorithm::HMACSHA512 => Some(ring::hmac::HMAC_SHA512),
+            HOTPAlgorithm::HMACSHA3_256 | HOTPAlgorithm::HMACSHA3_512 => None,
         }
     }
+
+    /// Returns the length of the HMAC output in bytes, which is also the length of generated secrets.
+    pub fn output_len(self) -> usize {
+        match self {
+            HOTPAlgorithm::HMACSHA1 => ring::digest::SHA1_OUTPUT_LEN,
+            HOTPAlgorithm::HMACSHA256 | HOTPAlgorithm::HMACSHA3_256 => 32,
+            HOTPAlgorithm::HMACSHA512 | HOTPAlgorithm::HMACSHA3_512 => 64,
+        }
+    }
+
+    /// Returns the block size of the underlying hash function in bytes, as used by HMAC to pad the key.
+    ///
+    /// For the SHA3 algorithms this is the sponge rate.
+    pub fn block_size(self) -> usize {
+        match self {
+            HOTPAlgorithm::HMACSHA1 | HOTPAlgorithm::HMACSHA256 => 64,
+            HOTPAlgorithm::HMACSHA512 => 128,
+            HOTPAlgorithm::HMACSHA3_256 => 136,
+            HOTPAlgorithm::HMACSHA3_512 => 72,
+        }
+    }
+}
+
+impl core::str::FromStr for HOTPAlgorithm {
+    type Err = OtpError;
+
+    /// Parses an algorithm name case-insensitively, such as `SHA1`, `HMACSHA256` or `hmac-sha512`.
+    fn from_str(s: &str) -> Result<HOTPAlgorithm, OtpError> {
+        let name: String = s.chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        match name.strip_prefix("HMAC").unwrap_or(&name) {
+            "SHA1" => Ok(HOTPAlgorithm::HMACSHA1),
+            "SHA256" => Ok(HOTPAlgorithm::HMACSHA256),
+            "SHA512" => Ok(HOTPAlgorithm::HMACSHA512),
+            "SHA3256" => Ok(HOTPAlgorithm::HMACSHA3_256),
+            "SHA3512" => Ok(HOTPAlgorithm::HMACSHA3_512),
+            _ => Err(OtpError::UnknownAlgorithm),
+        }
+    }
+}
+
+impl core::fmt::Display for HOTPAlgorithm {
+    /// Formats the algorithm as used by otpauth URIs: `SHA1`, `SHA256` or `SHA512`, or `SHA3-256` and `SHA3-512`.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            HOTPAlgorithm::HMACSHA1 => write!(f, "SHA1"),
+            HOTPAlgorithm::HMACSHA256 => write!(f, "SHA256"),
+            HOTPAlgorithm::HMACSHA512 => write!(f, "SHA512"),
+            HOTPAlgorithm::HMACSHA3_256 => write!(f, "SHA3-256"),
+            HOTPAlgorithm::HMACSHA3_512 => write!(f, "SHA3-512"),
+        }
+    }
+}
+
+/// Text encoding of a secret, e.g. one stored in an environment variable.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SecretFormat {
+    /// Detect hex or base32 from the character set and length of the secret, see `HOTP::from_hex_or_base32`.
+    #[default]
+    Auto,
+    /// RFC4648 base32, as used by otpauth URIs.
+    Base32,
+    /// Hex, upper or lower case.
+    Hex,
+    /// Standard or URL safe base64.
+    Base64,
 }
 
 /// This is the secret that will be used to generate HMAC based one-time-passwords.
 ///
 /// # References
 /// * This object implements utilities for [RFC4226](https://tools.ietf.org/html/rfc4226).
+#[derive(Clone)]
 pub struct HOTP {
     secret: Vec<u8>,
     algorithm: HOTPAlgorithm,
@@ -85,208 +402,1780 @@ impl HOTP {
     /// # Arguments
     ///
     /// * `algorithm` - Algorithm to use for OTP generation.
-    pub fn new(algorithm: HOTPAlgorithm) -> Result<HOTP, ring::error::Unspecified> {
-        let algo = algorithm.get_algorithm();
+    pub fn new(algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        HOTP::new_with_length(algorithm, SecretLength(algorithm.output_len()))
+    }
+
+    /// Creates a new HOTP secret using the provided random number generator.
+    ///
+    /// Useful for hardware RNGs on embedded targets, or deterministic RNGs in tests. The RNG must be
+    /// cryptographically secure for production secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    /// * `rng` - The random number generator to fill the secret with.
+    pub fn new_with_rng<R: rand_core::RngCore>(algorithm: HOTPAlgorithm, rng: &mut R) -> HOTP {
+        let mut secret = vec![0u8; algorithm.output_len()];
+        rng.fill_bytes(&mut secret);
+
+        HOTP {
+            secret,
+            algorithm,
+        }
+    }
 
-        match HOTP::generate_secret(algo.digest_algorithm().output_len()) {
+    /// Creates a new HOTP secret of `length` bytes from OS generated random number.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    /// * `length` - Length of the secret in bytes.
+    pub fn new_with_length(algorithm: HOTPAlgorithm, length: SecretLength) -> Result<HOTP, OtpError> {
+        match HOTP::generate_secret(length.get()) {
             Ok(secret) => {
-                Result::Ok(HOTP {
+                Ok(HOTP {
                     secret,
                     algorithm,
                 })
             },
-            Err(err) => {
-                Result::Err(err)
+            Err(_) => {
+                Err(OtpError::RngError)
             }
         }
     }
 
-    /// Loads a base32 encoded secret.
+    /// Creates an all zero secret of `length` bytes, ONLY for deterministic tests.
     ///
-    /// # Arguments
+    /// Every OTP of this secret is predictable, never use it outside of tests. Requires the `testing` feature.
     ///
-    /// * `data` - base32 encoded secret to load.
-    pub fn from_base32(data: &str) -> Result<HOTP, ()> {
-        // let secret = base32::decode(base32::Alphabet::RFC4648 {padding: false}, data);
-        let mut buffer = [0u8; 1024];
-        let secret = match binascii::b32decode(data.as_bytes(), &mut buffer) {
-            Ok(v) => v,
-            Err(_) => {
-                return Err(());
-            }
-        };
-
-        let algorithm = HOTPAlgorithm::from_buffer_len(secret.len());
-        match algorithm {
-            Some(algorithm) => {
-                Ok(HOTP{
-                    secret: Vec::from(secret),
-                    algorithm,
-                })
-            },
-            None => {
-                Err(())
-            }
+    /// # Arguments
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    /// * `length` - Length of the secret in bytes, the minimum secret length is not enforced.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn new_insecure_zeroed(algorithm: HOTPAlgorithm, length: usize) -> HOTP {
+        HOTP {
+            secret: vec![0u8; length],
+            algorithm,
         }
     }
 
-    pub fn from_base32_predefined_algorithm(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, ()> {
-        let mut buffer = [0u8; 1024];
-        let secret = match binascii::b32decode(data.as_bytes(), &mut buffer) {
-            Ok(v) => v,
-            Err(_) => {
-                return Err(());
+    /// Derives a deterministic secret from a passphrase and salt using PBKDF2-HMAC-SHA256.
+    ///
+    /// # Arguments
+    ///
+    /// * `passphrase` - The passphrase to derive the secret from.
+    /// * `salt` - Salt for the key derivation, should be unique per account.
+    /// * `iterations` - PBKDF2 iteration count, must be greater than 0.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_from_passphrase(passphrase: &[u8], salt: &[u8], iterations: u32, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let iterations = match core::num::NonZeroU32::new(iterations) {
+            Some(v) => v,
+            None => {
+                return Err(OtpError::InvalidIterations);
             }
         };
 
-        Ok(HOTP{
-            secret: Vec::from(secret),
+        let mut secret = vec![0u8; algorithm.output_len()];
+        ring::pbkdf2::derive(ring::pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase, &mut secret);
+
+        Ok(HOTP {
+            secret,
             algorithm,
         })
     }
 
-    /// Loads the HOTP secret from a given `[u8]`.
+    /// Derives a deterministic secret from a passphrase and salt, using `PBKDF2_DEFAULT_ITERATIONS`.
     ///
     /// # Arguments
-    /// * `data` - The shared secret.
-    /// * `algorithm` - Algorithm used for OTP generation.
-    pub fn from_bin(data: &[u8]) -> Result<HOTP, ()> {
-        let algorithm = HOTPAlgorithm::from_buffer_len(data.len());
-        if algorithm.is_none() {
-            return Result::Err(());
-        }
-        Result::Ok(HOTP {
-            secret: Vec::from(data),
-            algorithm: algorithm.unwrap(),
-        })
+    ///
+    /// * `passphrase` - The passphrase to derive the secret from.
+    /// * `salt` - Salt for the key derivation, should be unique per account.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_from_passphrase_default(passphrase: &[u8], salt: &[u8], algorithm: HOTPAlgorithm) -> HOTP {
+        HOTP::new_from_passphrase(passphrase, salt, PBKDF2_DEFAULT_ITERATIONS, algorithm)
+            .expect("default iteration count is not zero")
     }
 
-    fn generate_secret(size: usize) -> Result<Vec<u8>, ring::error::Unspecified> {
-        use ring::rand::SecureRandom;
+    /// Derives a deterministic secret from a user password and a site specific salt using PBKDF2-HMAC-SHA256,
+    /// as done by some OTP backup tools to avoid storing a separate secret.
+    ///
+    /// Returns `OtpError::WeakIterationCount` for less than `PBKDF2_MINIMUM_ITERATIONS` iterations, unless `force`
+    /// is set, e.g. to recreate secrets derived by a tool with a lower count.
+    ///
+    /// # Arguments
+    /// * `password` - The password to derive the secret from.
+    /// * `salt` - Salt for the key derivation, should be unique per site.
+    /// * `iterations` - PBKDF2 iteration count, must be greater than 0.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    /// * `force` - Accept iteration counts below `PBKDF2_MINIMUM_ITERATIONS`.
+    pub fn new_from_password(password: &[u8], salt: &[u8], iterations: u32, algorithm: HOTPAlgorithm, force: bool) -> Result<HOTP, OtpError> {
+        if iterations < PBKDF2_MINIMUM_ITERATIONS && !force {
+            return Err(OtpError::WeakIterationCount);
+        }
 
-        let mut secret: Vec<u8> = vec![0; size];
-        let rand = ring::rand::SystemRandom::new();
+        HOTP::new_from_passphrase(password, salt, iterations, algorithm)
+    }
 
-        match rand.fill(secret.as_mut()) {
-            Ok(_) => {
-                Result::Ok(secret)
-            },
-            Err(err) => {
-                Result::Err(err)
+    /// Derives a deterministic SHA1 secret from a user password and a site specific salt, using
+    /// `PBKDF2_DEFAULT_ITERATIONS`.
+    ///
+    /// # Arguments
+    /// * `password` - The password to derive the secret from.
+    /// * `salt` - Salt for the key derivation, should be unique per site.
+    pub fn new_from_password_default(password: &[u8], salt: &[u8]) -> HOTP {
+        HOTP::new_from_passphrase_default(password, salt, HOTPAlgorithm::HMACSHA1)
+    }
+
+    /// Derives a deterministic secret of `algorithm.output_len()` bytes from a device UUID.
+    ///
+    /// The 16 UUID bytes are expanded with HKDF-SHA256, an empty salt and the info `otp-secret-derivation`, so the
+    /// fixed version and variant bits don't end up in the secret. The derivation adds no entropy: the secret is
+    /// only as hard to guess as the UUID, so UUIDs used this way must be random (version 4) and kept private.
+    ///
+    /// Returns `OtpError::InvalidUuid` if `uuid` is neither hyphenated nor 32 bare hex digits.
+    ///
+    /// # Arguments
+    /// * `uuid` - The UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_from_uuid(uuid: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let hyphens = [8, 13, 18, 23];
+        let hex: String = match uuid.len() {
+            32 => String::from(uuid),
+            36 if hyphens.iter().all(|i| uuid.as_bytes()[*i] == b'-') => uuid.replace('-', ""),
+            _ => {
+                return Err(OtpError::InvalidUuid);
             }
+        };
+        if hex.len() != 32 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(OtpError::InvalidUuid);
         }
-    }
 
-    /// Exports the HOTP Secret as base32 encoded string.
-    pub fn get_secret_base32(&self) -> String {
-        let mut buffer = Box::new([0u8; 1024]);
-        match binascii::b32encode(self.secret.as_slice(), buffer.as_mut()) {
-            Ok(v) => {
-                let vec = Vec::from(v);
-                String::from_utf8(vec).unwrap()
-            },
-            Err(_) => unreachable!()
+        let mut bytes = vec![0u8; 16];
+        if binascii::hex2bin(hex.as_bytes(), &mut bytes).is_err() {
+            return Err(OtpError::InvalidUuid);
         }
+
+        let mut hotp = HOTP {
+            secret: bytes,
+            algorithm,
+        };
+        hotp.expand_with_hkdf(algorithm.output_len(), b"otp-secret-derivation")?;
+        Ok(hotp)
     }
 
-    /// Generates a **O**ne **T**ime **P**assword from the provided parameters.
+    /// Derives a secret of `algorithm.output_len()` bytes from physical dice throws, instead of the OS RNG.
     ///
-    /// # Arguments
+    /// The throws must carry at least 128 bits of entropy, e.g. 50 throws of a six-sided die, and are conditioned
+    /// into the secret with HKDF. Returns `OtpError::InsufficientEntropy` if there are too few throws, and
+    /// `OtpError::InvalidDiceThrow` if a throw is outside `1..=sides` or the die has less than 2 sides.
     ///
-    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
-    /// * `digits` - Desired OTP length, this value should be at least 6.
-    pub fn get_otp(&self, counter: &[u8], digits: u32) -> u32 {
-        let algorithm = self.algorithm.get_algorithm();
+    /// # Arguments
+    /// * `throws` - The outcome of every throw, in the order they were thrown.
+    /// * `sides` - The amount of sides of the die.
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_from_dice_throws(throws: &[u8], sides: u8, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        if sides < 2 || throws.iter().any(|throw| *throw == 0 || *throw > sides) {
+            return Err(OtpError::InvalidDiceThrow);
+        }
 
-        let signer = ring::hmac::Key::new(algorithm, self.secret.as_slice());
-        let hmac = ring::hmac::sign(&signer, counter);
-        let block = hmac.as_ref();
-        let num = HOTP::get_hotp_value(block);
+        // sides^throws overflows a u128 exactly when the throws carry at least 128 bits of entropy.
+        let mut outcomes: u128 = 1;
+        let mut is_sufficient = false;
+        for _ in throws {
+            match outcomes.checked_mul(sides as u128) {
+                Some(v) => outcomes = v,
+                None => {
+                    is_sufficient = true;
+                    break;
+                }
+            }
+        }
+        if !is_sufficient {
+            return Err(OtpError::InsufficientEntropy);
+        }
 
-        return num % 10u32.pow(digits);
+        Ok(HOTP::new_from_hkdf(throws, None, b"otp-dice-secret-derivation", algorithm))
     }
 
-    fn get_hotp_value(data: &[u8]) -> u32 {
-        let offset: usize = (data[data.len() - 1] & 0x0f) as usize;
+    /// Derives a secret of `algorithm.output_len()` bytes from a master secret with HKDF (RFC5869).
+    ///
+    /// HKDF uses the algorithm's own hash function, so the derived secret always has the ideal length for it.
+    /// Use a distinct `info` per purpose or account to derive independent secrets from the same master secret.
+    ///
+    /// # Arguments
+    /// * `ikm` - The input keying material, e.g. a high entropy master secret.
+    /// * `salt` - Optional salt, a string of zeros the length of the hash output is used without it.
+    /// * `info` - Context and application specific information for the derivation.
+    /// * `algorithm` - Algorithm to use for OTP generation, and for the derivation.
+    pub fn new_from_hkdf(ikm: &[u8], salt: Option<&[u8]>, info: &[u8], algorithm: HOTPAlgorithm) -> HOTP {
+        let salt = match salt {
+            Some(salt) => Vec::from(salt),
+            None => vec![0u8; algorithm.output_len()],
+        };
+        let extract = HOTP {
+            secret: salt,
+            algorithm,
+        };
+        let expand = HOTP {
+            secret: extract.hmac_raw(ikm),
+            algorithm,
+        };
 
-        let result: u32 = (((data[offset] & 0x7f) as u32) << 24)
-            | (((data[offset + 1] & 0xff) as u32) << 16)
-            | (((data[offset + 2] & 0xff) as u32) << 8)
-            | ((data[offset + 3] & 0xff) as u32);
-        return result;
+        // The output is exactly one hash long, so the first HKDF-Expand block T(1) is all of it.
+        let mut message = Vec::with_capacity(info.len() + 1);
+        message.extend_from_slice(info);
+        message.push(0x01);
+
+        HOTP {
+            secret: expand.hmac_raw(&message),
+            algorithm,
+        }
     }
 
-    /// Validates the given OTP
+    /// Loads a base32 encoded secret.
     ///
     /// # Arguments
-    /// * `counter` - The counter to test against.
-    /// * `digits` - The OTPs length.
-    /// * `guess` - A user provided guess to validate.
     ///
-    /// # Note
-    /// It is recommended to check the following counters in case the user skipped an OTP.
-    /// You should verify that an OTP with the same counter was not already used.
-    pub fn validate(&self, counter: &[u8], digits: u32, guess: u32) -> bool {
-        self.get_otp(counter, digits) == guess
-    }
-}
+    /// * `data` - base32 encoded secret to load, at least 16 bytes long once decoded.
+    pub fn from_base32(data: &str) -> Result<HOTP, OtpError> {
+        let secret = HOTP::decode_base32(data)?;
 
-/// Provides Time based One Time Passwords.
-///
-/// # References
-/// * This object implements utilities for [RFC6328](https://tools.ietf.org/html/rfc6238).
-pub struct TOTP {
-    secret: HOTP,
-    time_step: u64,
-    start_time: u64,
-}
+        match HOTPAlgorithm::from_buffer_len(secret.len()) {
+            Some(algorithm) => {
+                HOTP::with_valid_length(secret, algorithm)
+            },
+            None => {
+                Err(OtpError::UnknownAlgorithm)
+            }
+        }
+    }
 
-impl TOTP {
-    /// Creates a new TOTP instance.
+    /// Loads a base32 encoded secret that is known to be valid, without enforcing the minimum secret length.
     ///
     /// # Arguments
-    /// * `secret` - HOTP secret to use for TOTP generation.
-    /// * `time_step` - The time frame to allow every password, in seconds. RFC6238 recommends 30 seconds.
-    /// * `start_time` - Configurable T0 for OTP.
-    pub fn new(secret: HOTP, time_step: u64, start_time: u64) -> TOTP {
-        assert!(time_step > 0);
+    ///
+    /// * `data` - base32 encoded secret to load.
+    ///
+    /// # Panics
+    /// Panics if `data` is not valid base32, use `from_base32` for user provided input.
+    pub fn from_base32_unchecked(data: &str) -> HOTP {
+        let secret = HOTP::decode_base32(data).expect("invalid base32 secret");
+        let algorithm = HOTPAlgorithm::from_buffer_len(secret.len()).expect("unknown algorithm");
 
-        TOTP{
+        HOTP {
             secret,
-            time_step,
-            start_time,
+            algorithm,
         }
     }
 
-    fn get_time(&self) -> u64 {
-        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap();
-        return (now.as_secs() + self.start_time) / self.time_step;
+    pub fn from_base32_predefined_algorithm(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        HOTP::with_valid_length(HOTP::decode_base32(data)?, algorithm)
     }
 
-    /// Generates a time based OTP.
+    fn decode_base32(data: &str) -> Result<Vec<u8>, OtpError> {
+        let mut buffer = [0u8; 1024];
+        match binascii::b32decode(data.as_bytes(), &mut buffer) {
+            Ok(v) => Ok(Vec::from(v)),
+            Err(_) => Err(OtpError::InvalidBase32),
+        }
+    }
+
+    /// Loads the HOTP secret from a given `[u8]`.
     ///
     /// # Arguments
-    /// * `digits` - Desired OTP length, should be at least 6.
-    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
-    pub fn get_otp(&self, digits: u32, offset: i32) -> u32 {
-        let buf: &[u8] = &utils::num_to_buffer(((self.get_time() as i64) + (offset as i64)) as u64 );
-        return self.secret.get_otp(buf, digits);
+    /// * `data` - The shared secret, at least 16 bytes long.
+    pub fn from_bin(data: &[u8]) -> Result<HOTP, OtpError> {
+        let algorithm = HOTPAlgorithm::from_buffer_len(data.len());
+        if algorithm.is_none() {
+            return Result::Err(OtpError::UnknownAlgorithm);
+        }
+        HOTP::with_valid_length(Vec::from(data), algorithm.unwrap())
     }
 
-    /// Validates the given OTP.
+    /// Loads an ASCII string as the raw key bytes, without any decoding.
+    ///
+    /// Returns `OtpError::InvalidAscii` if `data` contains non ASCII characters.
     ///
     /// # Arguments
-    /// * `digits` - The amount of digits set for the OTP.
-    /// * `guess` - The user provided guess to validate.
-    /// * `buffer` - Amount of OTPs to check before and after the current one (0=Only current, 1=Previous+Now+Next OTP, etc...)
-    pub fn validate(&self, digits: u32, guess: u32, buffer: u32) -> bool {
-        for offset in -(buffer as i32)..((buffer + 1) as i32) {
-            if self.get_otp(digits, offset) == guess {
-                return true;
-            }
+    /// * `data` - The shared secret as text, at least 16 characters long.
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    pub fn from_ascii(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        if !data.is_ascii() {
+            return Err(OtpError::InvalidAscii);
         }
-        return false;
+
+        HOTP::with_valid_length(Vec::from(data.as_bytes()), algorithm)
     }
+
+    /// Loads the HOTP secret from a given `[u8]`, without enforcing the minimum secret length.
+    ///
+    /// # Arguments
+    /// * `data` - The shared secret.
+    pub fn from_bin_unchecked(data: &[u8]) -> HOTP {
+        HOTP {
+            secret: Vec::from(data),
+            algorithm: HOTPAlgorithm::from_buffer_len(data.len()).expect("unknown algorithm"),
+        }
+    }
+
+    /// Checks that the secret is at least 16 bytes long, as required by RFC4226 section 4.
+    pub fn validate_length(&self) -> Result<(), OtpError> {
+        if self.secret.len() < RFC_MINIMUM_SECRET_BYTES {
+            return Err(OtpError::SecretTooShort { actual: self.secret.len(), minimum: RFC_MINIMUM_SECRET_BYTES });
+        }
+        Ok(())
+    }
+
+    /// Audits the secret against RFC4226, returning every interoperability warning found.
+    ///
+    /// Warns for secrets shorter than 128 bits and for SHA3 algorithms, which are not part of the SHA-1 and SHA-2
+    /// algorithms listed by RFC4226 and RFC6238. The OTP length is not part of the secret, RFC4226 requires 6 to 8 digits.
+    pub fn validate_rfc4226(&self) -> Result<(), Vec<&'static str>> {
+        let mut warnings = Vec::new();
+        if self.secret.len() < RFC_MINIMUM_SECRET_BYTES {
+            warnings.push("secret is shorter than 128 bits");
+        }
+        if matches!(self.algorithm, HOTPAlgorithm::HMACSHA3_256 | HOTPAlgorithm::HMACSHA3_512) {
+            warnings.push("SHA3 algorithms are not covered by RFC4226 or RFC6238");
+        }
+
+        if warnings.is_empty() {
+            Ok(())
+        } else {
+            Err(warnings)
+        }
+    }
+
+    /// Estimates the entropy of the secret in bits, from the Shannon entropy of its byte distribution.
+    ///
+    /// A secret of `len` bytes can have at most `log2(min(len, 256))` bits of Shannon entropy per byte, far below
+    /// 8 bits for short secrets even when every byte is distinct. The estimate is therefore normalised against that
+    /// maximum and scaled to `8 * len` bits, which secrets with no repeated byte values reach.
+    ///
+    /// # Limitations
+    /// This is a diagnostic, not a security guarantee. The estimate only looks at how often each byte value occurs, so
+    /// it can't detect secrets that are predictable for other reasons, such as the hash of a password or the output
+    /// of a seeded RNG. Repeated or skewed bytes, as produced by broken RNGs or text keys, lower it noticeably.
+    #[cfg(feature = "std")]
+    pub fn entropy_estimate(&self) -> f64 {
+        if self.secret.len() < 2 {
+            return 0.0;
+        }
+
+        let mut counts = [0usize; 256];
+        for byte in self.secret.iter() {
+            counts[*byte as usize] += 1;
+        }
+
+        let len = self.secret.len() as f64;
+        let per_byte: f64 = counts.iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / len;
+                -p * p.log2()
+            })
+            .sum();
+        per_byte / len.min(256.0).log2() * 8.0 * len
+    }
+
+    /// Returns `true` if `entropy_estimate` exceeds 128 bits, the entropy of the RFC4226 minimum secret length.
+    ///
+    /// Secrets generated by `HOTP::new` pass, see `entropy_estimate` for the limitations of this check.
+    #[cfg(feature = "std")]
+    pub fn is_high_entropy(&self) -> bool {
+        self.entropy_estimate() > 128.0
+    }
+
+    /// Appends zero bytes to the secret until it is at least `minimum_bytes` long.
+    ///
+    /// This is meant for importing legacy secrets that are shorter than the RFC4226 minimum. HMAC already pads
+    /// short keys with zeros, so the generated OTPs don't change: the secret is exactly as weak as before.
+    /// Prefer rotating such secrets, or `expand_with_hkdf` when the OTPs are allowed to change.
+    ///
+    /// # Arguments
+    /// * `minimum_bytes` - The minimum secret length in bytes, usually `RFC_MINIMUM_SECRET_BYTES`.
+    pub fn pad_to_minimum_length(&mut self, minimum_bytes: usize) {
+        if self.secret.len() < minimum_bytes {
+            self.secret.resize(minimum_bytes, 0);
+        }
+    }
+
+    /// Replaces the secret with `target_len` bytes derived from it using HKDF-SHA256, with an empty salt.
+    ///
+    /// Unlike `pad_to_minimum_length` the derived secret generates different OTPs, so authenticators must be
+    /// provisioned again with it.
+    ///
+    /// # Arguments
+    /// * `target_len` - Length of the derived secret in bytes, at most 8160.
+    /// * `info` - Context and application specific information for the derivation.
+    pub fn expand_with_hkdf(&mut self, target_len: usize, info: &[u8]) -> Result<(), OtpError> {
+        struct OutputLen(usize);
+
+        impl ring::hkdf::KeyType for OutputLen {
+            fn len(&self) -> usize {
+                self.0
+            }
+        }
+
+        let prk = ring::hkdf::Salt::new(ring::hkdf::HKDF_SHA256, &[]).extract(&self.secret);
+        let info = [info];
+        let okm = match prk.expand(&info, OutputLen(target_len)) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(OtpError::KeyDerivation);
+            }
+        };
+
+        let mut secret = vec![0u8; target_len];
+        if okm.fill(&mut secret).is_err() {
+            return Err(OtpError::KeyDerivation);
+        }
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.secret);
+        self.secret = secret;
+        Ok(())
+    }
+
+    /// Creates a copy of this secret that generates OTPs with `new_algorithm`.
+    ///
+    /// Meant for algorithm migrations, where OTPs of both algorithms are accepted for a while. Reusing the same
+    /// key with several algorithms is best avoided, and a secret sized for SHA1 is shorter than the SHA256 or
+    /// SHA512 output: prefer deriving the new secret with `new_from_hkdf` when authenticators can be
+    /// provisioned again.
+    ///
+    /// # Arguments
+    /// * `new_algorithm` - Algorithm to use for OTP generation.
+    pub fn clone_with_new_algorithm(&self, new_algorithm: HOTPAlgorithm) -> HOTP {
+        HOTP {
+            secret: self.secret.clone(),
+            algorithm: new_algorithm,
+        }
+    }
+
+    /// Replaces the secret with `new_secret`, returning the previous secret for a grace period.
+    ///
+    /// Both secrets keep the same algorithm. With the `zeroize` feature the old bytes are wiped once the returned
+    /// HOTP is dropped, they are never copied. Returns `OtpError::SecretTooShort` and keeps the current secret if
+    /// `new_secret` is shorter than 16 bytes.
+    ///
+    /// # Security
+    /// While the old secret is still accepted, whoever knows either secret can pass verification, so a leaked secret
+    /// stays usable until the grace period ends. Keep it as short as possible, e.g. a single time frame, and drop the
+    /// old secret as soon as a code generated with the new one was verified.
+    ///
+    /// # Arguments
+    /// * `new_secret` - The new shared secret, at least 16 bytes long.
+    pub fn rotate(&mut self, new_secret: Vec<u8>) -> Result<HOTP, OtpError> {
+        let new_secret = HOTP::with_valid_length(new_secret, self.algorithm)?;
+        Ok(core::mem::replace(self, new_secret))
+    }
+
+    fn with_valid_length(secret: Vec<u8>, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let hotp = HOTP {
+            secret,
+            algorithm,
+        };
+        hotp.validate_length()?;
+        Ok(hotp)
+    }
+
+    fn generate_secret(size: usize) -> Result<Vec<u8>, ring::error::Unspecified> {
+        use ring::rand::SecureRandom;
+
+        let mut secret: Vec<u8> = vec![0; size];
+        let rand = ring::rand::SystemRandom::new();
+
+        match rand.fill(secret.as_mut()) {
+            Ok(_) => {
+                Result::Ok(secret)
+            },
+            Err(err) => {
+                Result::Err(err)
+            }
+        }
+    }
+
+    /// Exports the HOTP Secret as base32 encoded string.
+    pub fn get_secret_base32(&self) -> String {
+        let mut buffer = Box::new([0u8; 1024]);
+        match binascii::b32encode(self.secret.as_slice(), buffer.as_mut()) {
+            Ok(v) => {
+                let vec = Vec::from(v);
+                String::from_utf8(vec).unwrap()
+            },
+            Err(_) => unreachable!()
+        }
+    }
+
+    /// Loads a secret encoded with Crockford's base32 alphabet.
+    ///
+    /// Decoding is case-insensitive, ignores hyphens, and verifies a trailing check symbol when present.
+    ///
+    /// # Arguments
+    /// * `data` - Crockford base32 encoded secret to load, at least 16 bytes long once decoded.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    pub fn from_base32_crockford(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        match utils::crockford_decode(data) {
+            Some(secret) => HOTP::with_valid_length(secret, algorithm),
+            None => Err(OtpError::InvalidBase32),
+        }
+    }
+
+    /// Exports the HOTP Secret as an upper case Crockford base32 encoded string, without a check symbol.
+    pub fn get_secret_base32_crockford(&self) -> String {
+        utils::crockford_encode(&self.secret)
+    }
+
+    /// Loads a hex encoded secret.
+    ///
+    /// # Arguments
+    /// * `data` - hex encoded secret to load, upper or lower case. At least 16 bytes long once decoded.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    pub fn from_hex(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let mut buffer = vec![0u8; data.len() / 2];
+        let secret = match binascii::hex2bin(data.as_bytes(), &mut buffer) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(OtpError::InvalidHex);
+            }
+        };
+
+        HOTP::with_valid_length(Vec::from(secret), algorithm)
+    }
+
+    /// Loads a secret that is either hex or base32 encoded, detecting the encoding.
+    ///
+    /// Secrets of 40, 64 or 128 hex digits are decoded as hex, secrets made of `A-Z`, `2-7` and trailing `=` as base32.
+    /// Returns `OtpError::AmbiguousEncoding` if the secret could be both, e.g. 40 characters all made of `2-7` and
+    /// `A-F`, and `OtpError::InvalidEncoding` if it is neither. Use `from_hex_or_base32_with_hint` when the encoding is known.
+    ///
+    /// # Arguments
+    /// * `data` - hex or base32 encoded secret. At least 16 bytes long once decoded.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    pub fn from_hex_or_base32(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        HOTP::from_hex_or_base32_with_hint(data, algorithm, SecretFormat::Auto)
+    }
+
+    /// Loads a secret with the encoding given by `hint`, or detected as by `from_hex_or_base32` when `SecretFormat::Auto`.
+    ///
+    /// # Arguments
+    /// * `data` - encoded secret. At least 16 bytes long once decoded.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    /// * `hint` - Encoding of the secret, see `from_hex_or_base32` for the detection rules of `SecretFormat::Auto`.
+    pub fn from_hex_or_base32_with_hint(data: &str, algorithm: HOTPAlgorithm, hint: SecretFormat) -> Result<HOTP, OtpError> {
+        let format = match hint {
+            SecretFormat::Auto => {
+                let is_hex = matches!(data.len(), 40 | 64 | 128) && data.bytes().all(|c| c.is_ascii_hexdigit());
+                let is_base32 = !data.is_empty() && data.trim_end_matches('=').bytes().all(|c| matches!(c, b'A'..=b'Z' | b'2'..=b'7'));
+
+                match (is_hex, is_base32) {
+                    (true, true) => {
+                        return Err(OtpError::AmbiguousEncoding);
+                    },
+                    (true, false) => SecretFormat::Hex,
+                    (false, true) => SecretFormat::Base32,
+                    (false, false) => {
+                        return Err(OtpError::InvalidEncoding);
+                    },
+                }
+            },
+            hint => hint,
+        };
+
+        match format {
+            SecretFormat::Hex => HOTP::from_hex(data, algorithm),
+            SecretFormat::Base64 => HOTP::from_base64(data, algorithm),
+            SecretFormat::Base32 | SecretFormat::Auto => HOTP::from_base32_predefined_algorithm(data, algorithm),
+        }
+    }
+
+    /// Exports the HOTP Secret as a lower case hex encoded string.
+    pub fn get_secret_hex(&self) -> String {
+        let mut buffer = vec![0u8; self.secret.len() * 2];
+        match binascii::bin2hex(self.secret.as_slice(), &mut buffer) {
+            Ok(v) => {
+                let vec = Vec::from(v);
+                String::from_utf8(vec).unwrap()
+            },
+            Err(_) => unreachable!()
+        }
+    }
+
+    /// Loads a base64 encoded secret.
+    ///
+    /// Both the standard and the URL safe alphabets of RFC4648 are accepted, padding is optional.
+    ///
+    /// # Arguments
+    /// * `data` - base64 encoded secret to load, at least 16 bytes long once decoded.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    pub fn from_base64(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        if data.is_empty() {
+            return Err(OtpError::EmptySecret);
+        }
+
+        let unpadded = data.trim_end_matches('=');
+        let padding = data.len() - unpadded.len();
+        if unpadded.len() % 4 == 1 || padding > 2 || (padding > 0 && !data.len().is_multiple_of(4)) {
+            return Err(OtpError::InvalidBase64Padding);
+        }
+
+        let mut input: Vec<u8> = unpadded.bytes()
+            .map(|c| match c {
+                b'-' => b'+',
+                b'_' => b'/',
+                c => c,
+            })
+            .collect();
+        while !input.len().is_multiple_of(4) {
+            input.push(b'=');
+        }
+
+        let mut buffer = vec![0u8; input.len() / 4 * 3];
+        let secret = match binascii::b64decode(&input, &mut buffer) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(OtpError::InvalidBase64);
+            }
+        };
+
+        HOTP::with_valid_length(Vec::from(secret), algorithm)
+    }
+
+    /// Exports the HOTP Secret as standard base64 encoded string, without padding.
+    pub fn get_secret_base64(&self) -> String {
+        let mut buffer = vec![0u8; self.secret.len().div_ceil(3) * 4];
+        match binascii::b64encode(self.secret.as_slice(), &mut buffer) {
+            Ok(v) => {
+                let vec = Vec::from(v);
+                String::from_utf8(vec).unwrap().trim_end_matches('=').to_string()
+            },
+            Err(_) => unreachable!()
+        }
+    }
+
+    /// Loads a base58check encoded secret, as exported by some hardware wallets.
+    ///
+    /// The 4 byte checksum is verified and the leading version byte is dropped, whatever its value.
+    ///
+    /// # Arguments
+    /// * `data` - base58check encoded secret. At least 16 bytes long once decoded, without the version byte.
+    /// * `algorithm` - Algorithm used for OTP generation.
+    #[cfg(feature = "base58")]
+    pub fn from_base58(data: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let mut secret = match bs58::decode(data).with_check(None).into_vec() {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(OtpError::InvalidBase58);
+            }
+        };
+        if secret.is_empty() {
+            return Err(OtpError::InvalidBase58);
+        }
+        secret.remove(0);
+
+        HOTP::with_valid_length(secret, algorithm)
+    }
+
+    /// Exports the HOTP Secret as a base58check encoded string, with the version byte `0x00`.
+    #[cfg(feature = "base58")]
+    pub fn get_secret_base58(&self) -> String {
+        bs58::encode(&self.secret).with_check_version(0x00).into_string()
+    }
+
+    /// Returns the raw bytes of the shared secret.
+    pub fn as_slice(&self) -> &[u8] {
+        self.secret.as_slice()
+    }
+
+    /// Returns the length of the shared secret in bytes.
+    pub fn len(&self) -> usize {
+        self.secret.len()
+    }
+
+    /// Returns `true` if the shared secret is empty.
+    pub fn is_empty(&self) -> bool {
+        self.secret.is_empty()
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword from the provided parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, `Digits::SIX` or more is recommended.
+    pub fn get_otp(&self, counter: &[u8], digits: Digits) -> u32 {
+        let hmac = self.hmac_raw(counter);
+        let num = HOTP::get_hotp_value(&hmac);
+
+        ((num as u64) % 10u64.pow(digits.get())) as u32
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword from the provided parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `try_get_otp`.
+    #[deprecated(note = "use `get_otp` with `Digits`, or `try_get_otp`")]
+    pub fn get_otp_u32(&self, counter: &[u8], digits: u32) -> u32 {
+        self.get_otp(counter, Digits::expect_valid(digits))
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword from the provided parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, between 1 and 10. This value should be at least 6.
+    pub fn try_get_otp(&self, counter: &[u8], digits: u32) -> Result<u32, OtpError> {
+        Ok(self.get_otp(counter, Digits::try_new(digits)?))
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword for a numeric counter.
+    ///
+    /// The counter is encoded as 8 big-endian bytes, as described by RFC4226.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `try_get_otp`.
+    pub fn get_otp_counter(&self, counter: u64, digits: u32) -> u32 {
+        self.get_otp(&utils::num_to_buffer(counter), Digits::expect_valid(digits))
+    }
+
+    /// Computes the full HMAC of `message` with the secret and the configured algorithm, before any truncation.
+    ///
+    /// Useful for protocols such as OCRA that build on the untruncated HMAC output.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to authenticate, for HOTP this is the 8 byte big-endian counter.
+    pub fn hmac_raw(&self, message: &[u8]) -> Vec<u8> {
+        match self.algorithm {
+            HOTPAlgorithm::HMACSHA3_256 => utils::hmac_sign::<sha3::Sha3_256>(&self.secret, message),
+            HOTPAlgorithm::HMACSHA3_512 => utils::hmac_sign::<sha3::Sha3_512>(&self.secret, message),
+            _ => {
+                let algorithm = self.algorithm.ring_algorithm().unwrap();
+                let signer = ring::hmac::Key::new(algorithm, self.secret.as_slice());
+                Vec::from(ring::hmac::sign(&signer, message).as_ref())
+            },
+        }
+    }
+
+    /// Returns a short pronounceable fingerprint of the secret, e.g. `"KOBA-TUVE-RI"`.
+    ///
+    /// The fingerprint is the first 32 bits of `HMAC-SHA256(secret, "fingerprint")`, spelled as five
+    /// consonant-vowel syllables. It is one way and reveals nothing useful about the secret, so it can be read
+    /// out over a separate channel to confirm that both sides were provisioned with the same secret.
+    pub fn to_fingerprint(&self) -> String {
+        const CONSONANTS: &[u8; 20] = b"BCDFGHJKLMNPQRSTVWXZ";
+        const VOWELS: &[u8; 5] = b"AEIOU";
+
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, self.secret.as_slice());
+        let tag = ring::hmac::sign(&key, b"fingerprint");
+        let mut value = u32::from_be_bytes([tag.as_ref()[0], tag.as_ref()[1], tag.as_ref()[2], tag.as_ref()[3]]);
+
+        // 100^5 > 2^32, so five syllables spell every bit of the value.
+        let mut fingerprint = String::with_capacity(12);
+        for i in 0..5 {
+            if i == 2 || i == 4 {
+                fingerprint.push('-');
+            }
+            let syllable = (value % 100) as usize;
+            value /= 100;
+            fingerprint.push(CONSONANTS[syllable / VOWELS.len()] as char);
+            fingerprint.push(VOWELS[syllable % VOWELS.len()] as char);
+        }
+        fingerprint
+    }
+
+    /// Signs an arbitrary message with the secret, returning the full HMAC with the configured algorithm.
+    ///
+    /// This allows using the secret as a general purpose HMAC key, e.g. for webhook signatures or API request
+    /// signing. Same as `hmac_raw`. Don't reuse an OTP secret for other protocols, as anyone able to request
+    /// signatures of chosen 8 byte messages could compute its OTPs.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to sign.
+    pub fn sign_data(&self, message: &[u8]) -> Vec<u8> {
+        self.hmac_raw(message)
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword, zero-padded to exactly `digits` characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6. An empty string is returned for 0.
+    ///
+    /// # Panics
+    /// Panics if `digits` is greater than 10, see `try_get_otp`.
+    pub fn get_otp_string(&self, counter: &[u8], digits: u32) -> String {
+        if digits == 0 {
+            return String::new();
+        }
+
+        format!("{:0width$}", self.get_otp(counter, Digits::expect_valid(digits)), width = digits as usize)
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword for a numeric counter, zero-padded to exactly `digits` characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6. An empty string is returned for 0.
+    ///
+    /// # Panics
+    /// Panics if `digits` is greater than 10, see `try_get_otp`.
+    pub fn get_otp_string_for_counter(&self, counter: u64, digits: u32) -> String {
+        self.get_otp_string(&utils::num_to_buffer(counter), digits)
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword as 4 big-endian (network byte order) bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `try_get_otp`.
+    pub fn get_otp_as_bytes(&self, counter: &[u8], digits: u32) -> [u8; 4] {
+        self.get_otp(counter, Digits::expect_valid(digits)).to_be_bytes()
+    }
+
+    /// Generates a **O**ne **T**ime **P**assword as 4 little-endian bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - Password's counter. This counter value should never be reused for security reasons.
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `try_get_otp`.
+    pub fn get_otp_as_le_bytes(&self, counter: &[u8], digits: u32) -> [u8; 4] {
+        self.get_otp(counter, Digits::expect_valid(digits)).to_le_bytes()
+    }
+
+    fn get_hotp_value(data: &[u8]) -> u32 {
+        let offset: usize = (data[data.len() - 1] & 0x0f) as usize;
+
+        (((data[offset] & 0x7f) as u32) << 24)
+            | ((data[offset + 1] as u32) << 16)
+            | ((data[offset + 2] as u32) << 8)
+            | (data[offset + 3] as u32)
+    }
+
+    /// Validates the given OTP
+    ///
+    /// # Arguments
+    /// * `counter` - The counter to test against.
+    /// * `digits` - The OTPs length.
+    /// * `guess` - A user provided guess to validate.
+    ///
+    /// # Note
+    /// It is recommended to check the following counters in case the user skipped an OTP.
+    /// You should verify that an OTP with the same counter was not already used.
+    /// Guesses are rejected if `digits` is not between 1 and 10.
+    pub fn validate(&self, counter: &[u8], digits: u32, guess: u32) -> bool {
+        match Digits::try_new(digits) {
+            Ok(digits) => otp_eq(self.get_otp(counter, digits), guess),
+            Err(_) => false,
+        }
+    }
+
+    /// Verifies the given OTP in constant time.
+    ///
+    /// # Arguments
+    /// * `code` - A user provided code to verify.
+    /// * `expected_counter` - The counter to test against.
+    /// * `digits` - The OTPs length.
+    ///
+    /// # Note
+    /// You should verify that an OTP with the same counter was not already used.
+    /// Codes are rejected if `digits` is not between 1 and 10.
+    pub fn verify_otp(&self, code: u32, expected_counter: u64, digits: u32) -> bool {
+        self.validate(&utils::num_to_buffer(expected_counter), digits, code)
+    }
+
+    /// Verifies the given OTP against the counters `counter` to `counter + lookahead`.
+    ///
+    /// Returns the counter that matched, so the stored counter can be resynchronized as described in
+    /// [RFC4226 section 7.4](https://tools.ietf.org/html/rfc4226#section-7.4).
+    /// Every counter is checked, even after a match was found, so the time taken does not leak which counter matched.
+    ///
+    /// # Arguments
+    /// * `code` - A user provided code to verify.
+    /// * `counter` - The first counter to test against.
+    /// * `lookahead` - Amount of counters to check after `counter`.
+    /// * `digits` - The OTPs length.
+    pub fn verify_otp_with_lookahead(&self, code: u32, counter: u64, lookahead: u32, digits: u32) -> Option<u64> {
+        let mut matched = None;
+
+        for i in 0..=(lookahead as u64) {
+            let current = match counter.checked_add(i) {
+                Some(current) => current,
+                None => break,
+            };
+
+            if self.verify_otp(code, current, digits) && matched.is_none() {
+                matched = Some(current);
+            }
+        }
+
+        matched
+    }
+}
+
+/// Prints the algorithm and the length of the secret, but never the secret itself.
+impl core::fmt::Debug for HOTP {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("HOTP")
+            .field("algorithm", &self.algorithm)
+            .field("secret", &format_args!("[REDACTED {} bytes]", self.secret.len()))
+            .finish()
+    }
+}
+
+/// Compares the secrets in constant time, so `==` can be used in security sensitive code.
+impl PartialEq for HOTP {
+    fn eq(&self, other: &HOTP) -> bool {
+        self.algorithm == other.algorithm && utils::constant_time_eq(&self.secret, &other.secret)
+    }
+}
+
+impl Eq for HOTP {}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for HOTP {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+/// Wipes the secret from memory once it is no longer used.
+#[cfg(feature = "zeroize")]
+impl Drop for HOTP {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        self.secret.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for HOTP {}
+
+/// A HOTP secret together with its counter.
+///
+/// The counter is advanced every time an OTP is generated, so a counter value cannot accidentally be reused.
+#[derive(Clone)]
+pub struct HOTPCounter {
+    secret: HOTP,
+    counter: u64,
+}
+
+impl HOTPCounter {
+    /// Creates a new HOTPCounter.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for OTP generation.
+    /// * `counter` - The counter of the next OTP.
+    pub fn new(secret: HOTP, counter: u64) -> HOTPCounter {
+        HOTPCounter {
+            secret,
+            counter,
+        }
+    }
+
+    /// Generates the next OTP and advances the counter.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn next_otp(&mut self, digits: u32) -> u32 {
+        let otp = self.peek_next_otp(digits);
+        self.counter += 1;
+        otp
+    }
+
+    /// Generates the next OTP without advancing the counter.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn peek_next_otp(&self, digits: u32) -> u32 {
+        self.secret.get_otp_counter(self.counter, digits)
+    }
+
+    /// Returns the counter that will be used for the next OTP.
+    pub fn current_counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Overrides the counter, for resynchronization as described in [RFC4226 section 7.4](https://tools.ietf.org/html/rfc4226#section-7.4).
+    ///
+    /// # Arguments
+    /// * `value` - The counter of the next OTP.
+    pub fn set_counter(&mut self, value: u64) {
+        self.counter = value;
+    }
+
+    /// Resynchronizes the counter as described in [RFC4226 section 7.4](https://tools.ietf.org/html/rfc4226#section-7.4).
+    ///
+    /// Searches the counters `counter` to `counter + lookahead` for `presented_code`, and on success advances
+    /// the counter past the matched one. Returns the matched counter, so the drift can be logged.
+    ///
+    /// # Arguments
+    /// * `presented_code` - The user provided code.
+    /// * `digits` - The OTPs length.
+    /// * `lookahead` - The amount of counters to check after the current one.
+    pub fn sync(&mut self, presented_code: u32, digits: u32, lookahead: u32) -> Result<u64, OtpError> {
+        if let OtpValidationResult::Valid { counter_value, .. } = self.verify_with_lookahead(presented_code, digits, lookahead) {
+            return Ok(counter_value);
+        }
+        Err(OtpError::SyncFailed)
+    }
+
+    /// Verifies the given OTP against the counters `counter` to `counter + lookahead`, and on success advances
+    /// the counter past the matched one.
+    ///
+    /// A code matching the last used counter is reported as `OtpValidationResult::ReplayDetected`. A code matching
+    /// counter `u64::MAX` is reported as `OtpError::CounterExhausted`, as the counter can't be advanced past it.
+    ///
+    /// # Arguments
+    /// * `code` - The user provided code.
+    /// * `digits` - The OTPs length.
+    /// * `lookahead` - The amount of counters to check after the current one, at most `i32::MAX`.
+    pub fn verify_with_lookahead(&mut self, code: u32, digits: u32, lookahead: u32) -> OtpValidationResult {
+        if let Err(err) = Digits::try_new(digits) {
+            return OtpValidationResult::SecretError(err);
+        }
+
+        let lookahead = lookahead.min(i32::MAX as u32);
+        if let Some(matched) = self.secret.verify_otp_with_lookahead(code, self.counter, lookahead, digits) {
+            let next = match matched.checked_add(1) {
+                Some(v) => v,
+                None => {
+                    return OtpValidationResult::SecretError(OtpError::CounterExhausted);
+                }
+            };
+            let window_offset = (matched - self.counter) as i32;
+            self.counter = next;
+            return OtpValidationResult::Valid { window_offset, counter_value: matched };
+        }
+
+        match self.counter.checked_sub(1) {
+            Some(last) if self.secret.verify_otp(code, last, digits) => OtpValidationResult::ReplayDetected,
+            _ => OtpValidationResult::InvalidCode,
+        }
+    }
+}
+
+/// Iterates over the OTPs of consecutive counters, starting from a given counter.
+///
+/// The iterator ends after the OTP for counter `u64::MAX`.
+#[derive(Clone)]
+pub struct HOTPIterator {
+    secret: HOTP,
+    counter: Option<u64>,
+    digits: u32,
+}
+
+impl HOTPIterator {
+    /// Creates a new HOTPIterator.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for OTP generation.
+    /// * `start_counter` - The counter of the first OTP.
+    /// * `digits` - Desired OTP length, this value should be at least 6.
+    ///
+    /// # Panics
+    /// The iterator panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn new(secret: HOTP, start_counter: u64, digits: u32) -> HOTPIterator {
+        HOTPIterator {
+            secret,
+            counter: Some(start_counter),
+            digits,
+        }
+    }
+}
+
+impl Iterator for HOTPIterator {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let counter = self.counter?;
+        self.counter = counter.checked_add(1);
+        Some(self.secret.get_otp_counter(counter, self.digits))
+    }
+}
+
+/// Provides Time based One Time Passwords.
+///
+/// # References
+/// * This object implements utilities for [RFC6328](https://tools.ietf.org/html/rfc6238).
+#[derive(Clone)]
+pub struct TOTP<T = SystemTimeClock> {
+    secret: HOTP,
+    time_step: u64,
+    start_time: u64,
+    clock: T,
+    issuer: Option<String>,
+    account_name: Option<String>,
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for TOTP<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("TOTP")
+            .field("secret", &self.secret)
+            .field("time_step", &self.time_step)
+            .field("start_time", &self.start_time)
+            .field("clock", &self.clock)
+            .field("issuer", &self.issuer)
+            .field("account_name", &self.account_name)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TOTP {
+    /// Creates a new TOTP instance.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for TOTP generation.
+    /// * `time_step` - The time frame to allow every password. RFC6238 recommends 30 seconds.
+    /// * `start_time` - Configurable T0 for OTP, the Unix time from which time steps are counted.
+    pub fn new(secret: HOTP, time_step: TimeStep, start_time: u64) -> TOTP {
+        TOTP::new_with_clock(secret, time_step, start_time, SystemTimeClock)
+    }
+
+    /// Creates a new TOTP instance with a fresh random secret, a 30 seconds time step, counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `algorithm` - Algorithm to use for OTP generation, determines the secret length.
+    pub fn new_with_random_secret(algorithm: HOTPAlgorithm) -> Result<TOTP, OtpError> {
+        Ok(TOTP::new_default(HOTP::new(algorithm)?))
+    }
+
+    /// Creates a new TOTP instance with a fresh random secret, using the algorithm and time step of `policy`,
+    /// counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `policy` - The OTP parameters to use.
+    pub fn new_with_random_secret_and_policy(policy: OtpPolicy) -> Result<TOTP, OtpError> {
+        TOTP::new_with_policy(HOTP::new(policy.algorithm)?, policy, 0)
+    }
+
+    /// Creates a new TOTP instance using the time step of `policy`.
+    ///
+    /// TOTP doesn't store the OTP length, pass `policy.digits` when generating and verifying codes.
+    /// Returns `OtpError::AlgorithmMismatch` if the algorithm of `secret` isn't the policy's, use
+    /// `HOTP::clone_with_new_algorithm` to change it.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for TOTP generation, using the algorithm of the policy.
+    /// * `policy` - The OTP parameters to use.
+    /// * `start_time` - Configurable T0 for OTP, the Unix time from which time steps are counted.
+    pub fn new_with_policy(secret: HOTP, policy: OtpPolicy, start_time: u64) -> Result<TOTP, OtpError> {
+        if secret.algorithm != policy.algorithm {
+            return Err(OtpError::AlgorithmMismatch);
+        }
+        Ok(TOTP::new(secret, policy.time_step, start_time))
+    }
+
+    /// Creates a new TOTP instance with the RFC6238 recommended 30 seconds time step, counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for TOTP generation.
+    pub fn new_default(secret: HOTP) -> TOTP {
+        TOTP::new(secret, TimeStep::THIRTY_SECONDS, 0)
+    }
+
+    /// Creates a new HMAC-SHA1 TOTP instance with a 30 seconds time step, counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `secret` - The shared secret, at least 16 bytes long.
+    pub fn new_sha1_30s(secret: &[u8]) -> Result<TOTP, OtpError> {
+        TOTP::new_30s(secret, HOTPAlgorithm::HMACSHA1)
+    }
+
+    /// Creates a new HMAC-SHA256 TOTP instance with a 30 seconds time step, counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `secret` - The shared secret, at least 16 bytes long.
+    pub fn new_sha256_30s(secret: &[u8]) -> Result<TOTP, OtpError> {
+        TOTP::new_30s(secret, HOTPAlgorithm::HMACSHA256)
+    }
+
+    /// Creates a new HMAC-SHA512 TOTP instance with a 30 seconds time step, counted from the Unix epoch.
+    ///
+    /// # Arguments
+    /// * `secret` - The shared secret, at least 16 bytes long.
+    pub fn new_sha512_30s(secret: &[u8]) -> Result<TOTP, OtpError> {
+        TOTP::new_30s(secret, HOTPAlgorithm::HMACSHA512)
+    }
+
+    fn new_30s(secret: &[u8], algorithm: HOTPAlgorithm) -> Result<TOTP, OtpError> {
+        let secret = HOTP::with_valid_length(Vec::from(secret), algorithm)?;
+        Ok(TOTP::new_default(secret))
+    }
+
+    /// Creates a `TOTPBuilder` for configuring a TOTP step by step.
+    pub fn builder() -> TOTPBuilder {
+        TOTPBuilder::new()
+    }
+}
+
+impl<T: TimeProvider> TOTP<T> {
+    /// Creates a new TOTP instance that reads the current time from `clock`.
+    ///
+    /// # Arguments
+    /// * `secret` - HOTP secret to use for TOTP generation.
+    /// * `time_step` - The time frame to allow every password. RFC6238 recommends 30 seconds.
+    /// * `start_time` - Configurable T0 for OTP, the Unix time from which time steps are counted.
+    /// * `clock` - The source of the current time.
+    pub fn new_with_clock(secret: HOTP, time_step: TimeStep, start_time: u64, clock: T) -> TOTP<T> {
+        TOTP{
+            secret,
+            time_step: time_step.get(),
+            start_time,
+            clock,
+            issuer: None,
+            account_name: None,
+        }
+    }
+
+    /// Returns the provider or service this TOTP belongs to, if one was set.
+    pub fn issuer(&self) -> Option<&str> {
+        self.issuer.as_deref()
+    }
+
+    /// Returns the name of the account this TOTP belongs to, if one was set.
+    pub fn account_name(&self) -> Option<&str> {
+        self.account_name.as_deref()
+    }
+
+    /// Audits the TOTP against RFC6238, returning every interoperability warning found, including the ones of
+    /// `HOTP::validate_rfc4226`.
+    ///
+    /// Warns for time steps other than 30 or 60 seconds, start times other than 0, and SHA-1, which newer guidance
+    /// such as NIST SP 800-131A deprecates for new deployments.
+    pub fn validate_rfc6238(&self) -> Result<(), Vec<&'static str>> {
+        let mut warnings = self.secret.validate_rfc4226().err().unwrap_or_default();
+        if self.time_step != 30 && self.time_step != 60 {
+            warnings.push("time step is neither 30 nor 60 seconds");
+        }
+        if self.start_time != 0 {
+            warnings.push("start time is not the Unix epoch");
+        }
+        if self.secret.algorithm == HOTPAlgorithm::HMACSHA1 {
+            warnings.push("SHA-1 is deprecated");
+        }
+
+        if warnings.is_empty() {
+            Ok(())
+        } else {
+            Err(warnings)
+        }
+    }
+
+    /// Reads the clock, panicking if it fails. Use the `try_` methods to handle clock errors.
+    fn get_unix_time(&self) -> u64 {
+        match self.clock.unix_seconds() {
+            Ok(now) => now,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Returns the counter (T) of the current time frame, as used for OTP generation.
+    pub fn current_counter(&self) -> u64 {
+        self.counter_at(self.get_unix_time())
+    }
+
+    /// Returns the counter (T) of the time frame that contains the given Unix time.
+    ///
+    /// # Arguments
+    /// * `unix_seconds` - Seconds since the Unix epoch, times before `start_time` map to counter 0.
+    pub fn counter_at(&self, unix_seconds: u64) -> u64 {
+        unix_seconds.saturating_sub(self.start_time) / self.time_step
+    }
+
+    /// Returns the current time step index without truncating it, e.g. `2.5` half way through the third time frame.
+    ///
+    /// The integer part is the counter (T) of `current_counter`, the fractional part the position within the
+    /// time frame, with the one second precision of the clock.
+    pub fn get_fractional_window(&self) -> f64 {
+        self.get_unix_time().saturating_sub(self.start_time) as f64 / self.time_step as f64
+    }
+
+    /// Returns the amount of seconds left before the current time frame expires, between 1 and `time_step`.
+    pub fn time_remaining(&self) -> u64 {
+        self.time_step - self.time_elapsed()
+    }
+
+    /// Returns the amount of seconds since the current time frame started, between 0 and `time_step - 1`.
+    pub fn time_elapsed(&self) -> u64 {
+        self.get_unix_time().saturating_sub(self.start_time) % self.time_step
+    }
+
+    /// Returns the first and last Unix second of the current time frame, both inclusive.
+    pub fn get_window_boundaries(&self) -> (u64, u64) {
+        self.get_window_boundaries_at(self.get_unix_time())
+    }
+
+    /// Returns the first and last Unix second of the time frame that contains the given Unix time, both inclusive.
+    ///
+    /// Time frames are counted from `start_time` (T0), so the window for counter T starts at
+    /// `T * time_step + start_time`.
+    ///
+    /// # Arguments
+    /// * `unix_seconds` - Seconds since the Unix epoch, times before `start_time` map to the first time frame.
+    pub fn get_window_boundaries_at(&self, unix_seconds: u64) -> (u64, u64) {
+        let start = self.counter_at(unix_seconds)
+            .saturating_mul(self.time_step)
+            .saturating_add(self.start_time);
+        (start, start.saturating_add(self.time_step - 1))
+    }
+
+    /// Returns the moment the current time frame started, e.g. to align a refresh timer.
+    #[cfg(feature = "std")]
+    pub fn current_window_start(&self) -> std::time::SystemTime {
+        let (start, _) = self.get_window_boundaries();
+        std::time::UNIX_EPOCH + core::time::Duration::from_secs(start)
+    }
+
+    /// Returns the moment the next time frame starts, when the current OTP expires.
+    #[cfg(feature = "std")]
+    pub fn next_window_at(&self) -> std::time::SystemTime {
+        let (_, end) = self.get_window_boundaries();
+        std::time::UNIX_EPOCH + core::time::Duration::from_secs(end.saturating_add(1))
+    }
+
+    /// Returns the fraction of the current time frame that has elapsed, in `[0.0, 1.0)`.
+    pub fn time_window_progress(&self) -> f64 {
+        self.time_window_progress_at(self.get_unix_time())
+    }
+
+    /// Returns the fraction of the time frame containing the given Unix time that has elapsed, in `[0.0, 1.0)`.
+    ///
+    /// # Arguments
+    /// * `unix_seconds` - Seconds since the Unix epoch, exactly 0.0 at the start of a time frame.
+    pub fn time_window_progress_at(&self, unix_seconds: u64) -> f64 {
+        let elapsed = unix_seconds.saturating_sub(self.start_time) % self.time_step;
+        elapsed as f64 / self.time_step as f64
+    }
+
+    /// Generates a time based OTP.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_otp(&self, digits: u32, offset: i32) -> u32 {
+        self.get_otp_for_counter(self.current_counter(), digits, offset)
+    }
+
+    /// Generates the current time based OTP together with the time left before it expires.
+    ///
+    /// Both come from the same clock reading, so the countdown always belongs to the returned code.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn current_otp_and_expiry(&self, digits: u32) -> (u32, core::time::Duration) {
+        let now = self.get_unix_time();
+        let remaining = self.time_step - now.saturating_sub(self.start_time) % self.time_step;
+
+        (self.get_otp_for_counter(self.counter_at(now), digits, 0), core::time::Duration::from_secs(remaining))
+    }
+
+    /// Generates the time based OTP of one time frame for several lengths, e.g. while migrating from 6 to 8 digits.
+    ///
+    /// The HMAC is computed once, so every code belongs to the same time frame even when called at its boundary.
+    /// Returns `(digits, otp)` pairs in the order of `digit_lengths`.
+    ///
+    /// # Arguments
+    /// * `digit_lengths` - Desired OTP lengths, should be at least 6.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    ///
+    /// # Panics
+    /// Panics if any of `digit_lengths` is not between 1 and 10, or the clock can't be read, see `Digits::try_new`.
+    pub fn get_otp_multiple_digits(&self, digit_lengths: &[u32], offset: i32) -> Vec<(u32, u32)> {
+        let counter = ((self.current_counter() as i64) + (offset as i64)) as u64;
+        let hmac = self.secret.hmac_raw(&utils::num_to_buffer(counter));
+        let num = HOTP::get_hotp_value(&hmac);
+
+        digit_lengths.iter().map(|&digits| {
+            (digits, ((num as u64) % 10u64.pow(Digits::expect_valid(digits).get())) as u32)
+        }).collect()
+    }
+
+    /// Generates a time based OTP together with the counter (T) it was generated for.
+    ///
+    /// Both come from the same clock reading, e.g. for audit logs or to deduplicate OTP uses.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_otp_with_counter(&self, digits: u32, offset: i32) -> (u32, u64) {
+        let counter = ((self.current_counter() as i64) + (offset as i64)) as u64;
+        (self.get_otp_for_counter(counter, digits, 0), counter)
+    }
+
+    /// Generates the OTP of the previous time frame, same as `get_otp(digits, -1)`.
+    ///
+    /// Useful for accepting a code that the user entered just as it expired.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_prev_otp(&self, digits: u32) -> u32 {
+        self.get_otp(digits, -1)
+    }
+
+    /// Generates the OTP of the next time frame, same as `get_otp(digits, 1)`.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_next_otp(&self, digits: u32) -> u32 {
+        self.get_otp(digits, 1)
+    }
+
+    /// Generates a time based OTP, without panicking if the clock can't be read.
+    ///
+    /// Returns `OtpError::ClockError` if the clock can't be read or is set before `start_time`,
+    /// and `OtpError::InvalidOffset` if `offset` points before the first time frame.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, between 1 and 10. This value should be at least 6.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    pub fn try_get_otp(&self, digits: u32, offset: i32) -> Result<u32, OtpError> {
+        let now = self.clock.unix_seconds()?;
+        if now < self.start_time {
+            return Err(OtpError::ClockError);
+        }
+
+        let counter = match self.counter_at(now).checked_add_signed(offset as i64) {
+            Some(v) => v,
+            None => {
+                return Err(OtpError::InvalidOffset);
+            }
+        };
+        self.secret.try_get_otp(&utils::num_to_buffer(counter), digits)
+    }
+
+    fn get_otp_for_counter(&self, counter: u64, digits: u32, offset: i32) -> u32 {
+        let buf: &[u8] = &utils::num_to_buffer(((counter as i64) + (offset as i64)) as u64 );
+        self.secret.get_otp(buf, Digits::expect_valid(digits))
+    }
+
+    /// Generates the time based OTPs of several consecutive time frames.
+    ///
+    /// Returns `(offset, otp)` pairs, all relative to the same current time frame.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    /// * `from_offset` - The first offset to generate, e.g. -1 for the previous time frame.
+    /// * `to_offset` - The last offset to generate (inclusive).
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `Digits::try_new`.
+    pub fn get_otp_range(&self, digits: u32, from_offset: i32, to_offset: i32) -> Vec<(i32, u32)> {
+        let counter = self.current_counter();
+
+        (from_offset..=to_offset)
+            .map(|offset| (offset, self.get_otp_for_counter(counter, digits, offset)))
+            .collect()
+    }
+
+    /// Returns every distinct OTP that `verify_with_window` would currently accept.
+    ///
+    /// Codes are ordered by offset, from `-window` to `window`, codes repeated by several frames are only listed once.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    /// * `window` - Amount of frames before and after the current one (0=Only current, 1=Previous+Now+Next, etc...)
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `Digits::try_new`.
+    pub fn get_all_valid_otps(&self, digits: u32, window: u32) -> Vec<u32> {
+        let mut otps = Vec::with_capacity(2 * window as usize + 1);
+
+        for (_, otp) in self.get_otp_range(digits, -(window as i32), window as i32) {
+            if !otps.contains(&otp) {
+                otps.push(otp);
+            }
+        }
+        otps
+    }
+
+    /// Generates the time based OTP that is valid at the given Unix time.
+    ///
+    /// # Arguments
+    /// * `unix_timestamp` - The time to generate the OTP for, in seconds since the Unix epoch.
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn get_otp_at(&self, unix_timestamp: u64, digits: u32) -> u32 {
+        let buf: &[u8] = &utils::num_to_buffer(self.counter_at(unix_timestamp));
+        self.secret.get_otp(buf, Digits::expect_valid(digits))
+    }
+
+    /// Generates the time based OTP of a fractional time step index, as returned by `get_fractional_window`.
+    ///
+    /// # Arguments
+    /// * `t` - The time step index, rounded down to the counter. Negative values map to counter 0.
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn get_otp_at_fractional(&self, t: f64, digits: u32) -> u32 {
+        let buf: &[u8] = &utils::num_to_buffer(t as u64);
+        self.secret.get_otp(buf, Digits::expect_valid(digits))
+    }
+
+    /// Generates the OTP of every time frame that lies completely between two Unix times.
+    ///
+    /// Returns `(window_start, otp)` pairs in chronological order, which only depend on the secret and the
+    /// time range, e.g. to generate test fixtures.
+    ///
+    /// # Arguments
+    /// * `start_unix` - The first Unix second of the range.
+    /// * `end_unix` - The last Unix second of the range (inclusive).
+    /// * `digits` - Desired OTP length, should be at least 6.
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, see `Digits::try_new`.
+    pub fn simulate_otp_sequence(&self, start_unix: u64, end_unix: u64, digits: u32) -> Vec<(u64, u32)> {
+        let mut sequence = Vec::new();
+
+        let (mut window_start, _) = self.get_window_boundaries_at(start_unix);
+        if window_start < start_unix {
+            window_start = window_start.saturating_add(self.time_step);
+        }
+
+        while let Some(window_end) = window_start.checked_add(self.time_step - 1) {
+            if window_end > end_unix {
+                break;
+            }
+            sequence.push((window_start, self.get_otp_at(window_start, digits)));
+
+            window_start = match window_end.checked_add(1) {
+                Some(v) => v,
+                None => break,
+            };
+        }
+        sequence
+    }
+
+    /// Generates a time based OTP together with the time frame it is valid in.
+    ///
+    /// The clock is only read once, so the code and its validity always belong to the same time frame.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    ///
+    /// # Panics
+    /// Panics if `digits` is not between 1 and 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_otp_with_validity(&self, digits: u32, offset: i32) -> TemporalOtp {
+        let counter = ((self.current_counter() as i64) + (offset as i64)) as u64;
+        let code = self.get_otp_for_counter(counter, digits, 0);
+        let valid_from = counter
+            .saturating_mul(self.time_step)
+            .saturating_add(self.start_time);
+
+        TemporalOtp {
+            code,
+            valid_from,
+            valid_until: valid_from.saturating_add(self.time_step - 1),
+            formatted: format!("{:0width$}", code, width = digits as usize),
+        }
+    }
+
+    /// Generates a time based OTP, zero-padded to exactly `digits` characters.
+    ///
+    /// # Arguments
+    /// * `digits` - Desired OTP length, should be at least 6. An empty string is returned for 0.
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    ///
+    /// # Panics
+    /// Panics if `digits` is greater than 10, or the clock can't be read, see `try_get_otp`.
+    pub fn get_otp_string(&self, digits: u32, offset: i32) -> String {
+        let buf: &[u8] = &utils::num_to_buffer(((self.current_counter() as i64) + (offset as i64)) as u64 );
+        self.secret.get_otp_string(buf, digits)
+    }
+
+    /// Validates the given OTP.
+    ///
+    /// # Arguments
+    /// * `digits` - The amount of digits set for the OTP.
+    /// * `guess` - The user provided guess to validate.
+    /// * `buffer` - Amount of OTPs to check before and after the current one (0=Only current, 1=Previous+Now+Next OTP, etc...)
+    pub fn validate(&self, digits: u32, guess: u32, buffer: u32) -> bool {
+        self.verify_with_window(guess, digits, buffer).is_accepted()
+    }
+
+    /// Verifies the given OTP against the current time frame.
+    ///
+    /// The comparison is done in constant time with `otp_eq`, so that the time taken does not leak
+    /// how many digits of `code` were correct.
+    ///
+    /// # Arguments
+    /// * `code` - The user provided code to verify.
+    /// * `digits` - The amount of digits set for the OTP.
+    pub fn verify(&self, code: u32, digits: u32) -> bool {
+        self.verify_with_window(code, digits, 0).is_accepted()
+    }
+
+    /// Verifies the given OTP against the current time frame and `window` frames before and after it.
+    ///
+    /// Every frame in the window is checked, even after a match was found, so the time taken does not leak which
+    /// frame matched. When several frames match, the one closest to the current frame is reported.
+    /// Codes are rejected if `digits` is not between 1 and 10.
+    ///
+    /// # Arguments
+    /// * `code` - The user provided code to verify.
+    /// * `digits` - The amount of digits set for the OTP.
+    /// * `window` - Amount of frames to check before and after the current one (0=Only current, 1=Previous+Now+Next, etc...)
+    pub fn verify_with_window(&self, code: u32, digits: u32, window: u32) -> TotpVerificationResult {
+        self.verify_with_window_at_counter(code, digits, window, self.current_counter())
+    }
+
+    fn verify_with_window_at_counter(&self, code: u32, digits: u32, window: u32, counter: u64) -> TotpVerificationResult {
+        let mut result = TotpVerificationResult::Rejected;
+        if Digits::try_new(digits).is_err() {
+            return result;
+        }
+
+        for offset in -(window as i32)..((window + 1) as i32) {
+            if !otp_eq(self.get_otp_for_counter(counter, digits, offset), code) {
+                continue;
+            }
+
+            let is_closer = match result {
+                TotpVerificationResult::Accepted { window_offset } => offset.abs() < window_offset.abs(),
+                TotpVerificationResult::Rejected => true,
+            };
+            if is_closer {
+                result = TotpVerificationResult::Accepted { window_offset: offset };
+            }
+        }
+
+        result
+    }
+}
+
+/// The outcome of verifying a TOTP against a window of time frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TotpVerificationResult {
+    /// The code matched the time frame at `window_offset` (0 for current, -1 for previous, 1 for next, etc...)
+    ///
+    /// A negative offset means the client's clock is behind, a positive one that it is ahead.
+    Accepted { window_offset: i32 },
+    /// The code did not match any time frame in the window.
+    Rejected,
+}
+
+impl TotpVerificationResult {
+    /// Returns `true` if the code was accepted.
+    pub fn is_accepted(&self) -> bool {
+        matches!(*self, TotpVerificationResult::Accepted { .. })
+    }
+}
+
+/// A time based OTP bundled with the time frame it is valid in, as returned by `TOTP::get_otp_with_validity`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporalOtp {
+    /// The OTP value.
+    pub code: u32,
+    /// The first Unix second the code is valid at.
+    pub valid_from: u64,
+    /// The last Unix second the code is valid at (inclusive).
+    pub valid_until: u64,
+    /// The code, zero-padded to the requested amount of digits.
+    pub formatted: String,
+}
+
+impl TemporalOtp {
+    /// Returns `true` if the code is valid at the given Unix time.
+    ///
+    /// # Arguments
+    /// * `unix_seconds` - Seconds since the Unix epoch.
+    pub fn is_valid_at(&self, unix_seconds: u64) -> bool {
+        (self.valid_from..=self.valid_until).contains(&unix_seconds)
+    }
+
+    /// Returns `true` if the code is valid at the current system time, `false` if the clock can't be read.
+    #[cfg(feature = "std")]
+    pub fn is_valid_now(&self) -> bool {
+        match SystemTimeClock.unix_seconds() {
+            Ok(now) => self.is_valid_at(now),
+            Err(_) => false,
+        }
+    }
+}
+
+/// The outcome of a stateful OTP validation, which also detects reused codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtpValidationResult {
+    /// The code matched the counter `counter_value`, `window_offset` counters away from the expected one.
+    Valid { window_offset: i32, counter_value: u64 },
+    /// The code did not match any counter.
+    InvalidCode,
+    /// The code matched a counter that was already used.
+    ReplayDetected,
+    /// The code could not be checked, e.g. because the clock could not be read.
+    SecretError(OtpError),
+}
+
+impl OtpValidationResult {
+    /// Returns `true` if the code was valid.
+    pub fn is_valid(&self) -> bool {
+        matches!(*self, OtpValidationResult::Valid { .. })
+    }
+}
+
+/// Compares two OTPs in constant time.
+///
+/// Both codes are compared as zero-padded 10 digit strings, so the time taken does not depend on
+/// how many digits match. Use it instead of `==` when building custom verification loops.
+///
+/// # Arguments
+/// * `a` - The first OTP.
+/// * `b` - The second OTP.
+pub fn otp_eq(a: u32, b: u32) -> bool {
+    let a = format!("{:010}", a);
+    let b = format!("{:010}", b);
+
+    utils::constant_time_eq(a.as_bytes(), b.as_bytes())
 }
 
 /// HMAC One Time Password function
@@ -294,12 +2183,13 @@ impl TOTP {
 /// # Arguments
 /// * `counter` - A 64-bit counter for HOTP
 /// * `secret` - A base32 encoded secret
-/// * `digits` - Desired OTP length in digits. 6+ is recommended.
+/// * `digits` - Desired OTP length in digits, between 1 and 10. 6+ is recommended.
 ///
 /// # Notes
 /// This function converts the counter to it's 64 bit little endian representation.
 /// If you have other requirements, please use the HOTP struct directly.
 pub fn hotp(counter: u64, secret: &str, digits: u32) -> Option<u32> {
+    let digits = Digits::try_new(digits).ok()?;
     match HOTP::from_base32(secret) {
         Ok(otp) => {
             let counter_bytes = &utils::num_to_buffer(counter);
@@ -315,7 +2205,7 @@ pub fn hotp(counter: u64, secret: &str, digits: u32) -> Option<u32> {
 ///
 /// # Arguments
 /// * `secret` - base32 encoded shared-secret.
-/// * `digits` - Desired OTP length in digits. 6+ is recommended.
+/// * `digits` - Desired OTP length in digits, between 1 and 10. 6+ is recommended.
 /// * `time_step` - Time frame for OTP is seconds.
 /// * `time_start` - Beginning of time for this TOTP.
 ///
@@ -335,7 +2225,10 @@ pub fn hotp(counter: u64, secret: &str, digits: u32) -> Option<u32> {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub fn totp(secret: &str, digits: u32, time_step: u64, time_start: u64) -> Option<String> {
+    let time_step = TimeStep::new(time_step).ok()?;
+    Digits::try_new(digits).ok()?;
     match HOTP::from_base32(secret) {
         Ok(otp) => {
             let totp = TOTP::new(otp, time_step, time_start).get_otp(digits, 0);
@@ -348,7 +2241,10 @@ pub fn totp(secret: &str, digits: u32, time_step: u64, time_start: u64) -> Optio
     }
 }
 
+#[cfg(feature = "std")]
 pub fn totp_override(secret: &str, digits: u32, time_step: u64, time_start: u64, algorithm: HOTPAlgorithm) -> Option<String> {
+    let time_step = TimeStep::new(time_step).ok()?;
+    Digits::try_new(digits).ok()?;
     match HOTP::from_base32_predefined_algorithm(secret, algorithm) {
         Ok(otp) => {
             let totp = TOTP::new(otp, time_step, time_start).get_otp(digits, 0);
@@ -361,6 +2257,7 @@ pub fn totp_override(secret: &str, digits: u32, time_step: u64, time_start: u64,
     }
 }
 
+#[cfg(feature = "std")]
 fn totp_u23_to_string(totp: u32, expected_digits_len: u32) -> String {
     let string_totp = totp.to_string();
     let digit_difference: usize = (expected_digits_len as usize) - string_totp.len();
@@ -369,7 +2266,7 @@ fn totp_u23_to_string(totp: u32, expected_digits_len: u32) -> String {
         return string_totp;
     }
 
-    return String::from_utf8(vec![b'0'; digit_difference]).unwrap() + &string_totp
+    String::from_utf8(vec![b'0'; digit_difference]).unwrap() + &string_totp
 }
 
 /// Validates HOTP inputs
@@ -379,16 +2276,17 @@ fn totp_u23_to_string(totp: u32, expected_digits_len: u32) -> String {
 /// * `validation_margin` - The validation will check this amount of OTPs before and after the current one.
 /// * `counter` - End user's currnet OTP counter.
 /// * `secret` - base32 encoded shared-secret.
-/// * `digits` - OTP length in digits. At least 6 is recommended.
+/// * `digits` - OTP length in digits, between 1 and 10. At least 6 is recommended.
 ///
 /// # Notes
 /// The program using this function should check that the provided input was not already used.
 pub fn validate_hotp(input: u32, validation_margin: i32, counter: u64, secret: &str, digits: u32) -> Option<bool> {
+    Digits::try_new(digits).ok()?;
     match HOTP::from_base32(secret) {
         Ok(hotp) => {
             for i in (-validation_margin)..(validation_margin+1) {
                 let current_counter = (counter as i64) + (i as i64);
-                if hotp.get_otp(&utils::num_to_buffer(current_counter as u64), digits) == input {
+                if otp_eq(hotp.get_otp_counter(current_counter as u64, digits), input) {
                     return Option::Some(true);
                 }
             }
@@ -406,10 +2304,13 @@ pub fn validate_hotp(input: u32, validation_margin: i32, counter: u64, secret: &
 /// * `input` - End user provided input
 /// * `validation_margin` - Checks this amount of OTP steps before and after the current OTP.
 /// * `secret` - A base32 encoded shared-secret.
-/// * `digits` - OTP length in digits. At least 6 is recommended.
+/// * `digits` - OTP length in digits, between 1 and 10. At least 6 is recommended.
 /// * `time_step` - Time frame for OTPs.
 /// * `time_start` - The beginning of time for this OTP (T0).
+#[cfg(feature = "std")]
 pub fn validate_totp(input: u32, validation_margin: u32, secret: &str, digits: u32, time_step: u64, time_start: u64) -> Option<bool> {
+    let time_step = TimeStep::new(time_step).ok()?;
+    Digits::try_new(digits).ok()?;
     match HOTP::from_base32(secret) {
         Ok(hotp) => {
             let totp = TOTP::new(hotp, time_step, time_start);
@@ -0,0 +1,269 @@
+use super::*;
+
+// Field numbers of the `MigrationPayload` and `OtpParameters` protobuf messages used by Google Authenticator.
+const PAYLOAD_OTP_PARAMETERS: u64 = 1;
+const PAYLOAD_VERSION: u64 = 2;
+const PAYLOAD_BATCH_SIZE: u64 = 3;
+const PAYLOAD_BATCH_INDEX: u64 = 4;
+const PARAMETERS_SECRET: u64 = 1;
+const PARAMETERS_NAME: u64 = 2;
+const PARAMETERS_ISSUER: u64 = 3;
+const PARAMETERS_ALGORITHM: u64 = 4;
+const PARAMETERS_DIGITS: u64 = 5;
+const PARAMETERS_TYPE: u64 = 6;
+const PARAMETERS_COUNTER: u64 = 7;
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LENGTH_DELIMITED: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+const OTP_TYPE_HOTP: u64 = 1;
+const OTP_TYPE_TOTP: u64 = 2;
+
+impl HOTP {
+    /// Encodes OTP accounts as a Google Authenticator migration payload.
+    ///
+    /// The payload is the protobuf message carried base64 encoded in the `data` parameter of
+    /// `otpauth-migration://offline` URIs.
+    ///
+    /// Returns `OtpError::UnknownAlgorithm` for SHA3 secrets, `OtpError::InvalidDigits` for codes other than
+    /// 6 or 8 digits, and `OtpError::InvalidTimeStep` for TOTP accounts not using a 30 seconds time step, as
+    /// Google Authenticator cannot represent them.
+    ///
+    /// # Arguments
+    /// * `entries` - The accounts to export.
+    pub fn to_google_migration_payload(entries: &[OtpUri]) -> Result<Vec<u8>, OtpError> {
+        let mut payload = Vec::new();
+
+        for entry in entries {
+            let algorithm = match entry.secret.algorithm {
+                HOTPAlgorithm::HMACSHA1 => 1,
+                HOTPAlgorithm::HMACSHA256 => 2,
+                HOTPAlgorithm::HMACSHA512 => 3,
+                HOTPAlgorithm::HMACSHA3_256 | HOTPAlgorithm::HMACSHA3_512 => {
+                    return Err(OtpError::UnknownAlgorithm);
+                }
+            };
+            let digits = match entry.digits.get() {
+                6 => 1,
+                8 => 2,
+                _ => {
+                    return Err(OtpError::InvalidDigits);
+                }
+            };
+
+            let mut parameters = Vec::new();
+            write_bytes(&mut parameters, PARAMETERS_SECRET, &entry.secret.secret);
+            write_bytes(&mut parameters, PARAMETERS_NAME, entry.account.as_bytes());
+            if let Some(issuer) = &entry.issuer {
+                write_bytes(&mut parameters, PARAMETERS_ISSUER, issuer.as_bytes());
+            }
+            write_varint_field(&mut parameters, PARAMETERS_ALGORITHM, algorithm);
+            write_varint_field(&mut parameters, PARAMETERS_DIGITS, digits);
+            match entry.kind {
+                OtpUriKind::Totp { period } => {
+                    if period != DEFAULT_TIME_STEP {
+                        return Err(OtpError::InvalidTimeStep);
+                    }
+                    write_varint_field(&mut parameters, PARAMETERS_TYPE, OTP_TYPE_TOTP);
+                },
+                OtpUriKind::Hotp { counter } => {
+                    write_varint_field(&mut parameters, PARAMETERS_TYPE, OTP_TYPE_HOTP);
+                    write_varint_field(&mut parameters, PARAMETERS_COUNTER, counter);
+                },
+            }
+
+            write_bytes(&mut payload, PAYLOAD_OTP_PARAMETERS, &parameters);
+        }
+
+        write_varint_field(&mut payload, PAYLOAD_VERSION, 1);
+        write_varint_field(&mut payload, PAYLOAD_BATCH_SIZE, 1);
+        write_varint_field(&mut payload, PAYLOAD_BATCH_INDEX, 0);
+        Ok(payload)
+    }
+
+    /// Parses a Google Authenticator migration payload into OTP accounts.
+    ///
+    /// Decode the base64 `data` parameter of the `otpauth-migration://offline` URI before calling this.
+    /// Google Authenticator only supports a 30 seconds time step, so every TOTP account uses it.
+    ///
+    /// # Arguments
+    /// * `data` - The protobuf encoded migration payload.
+    ///
+    /// # Notes
+    /// Authenticator apps commonly use 10 byte secrets, so the minimum secret length is not enforced.
+    pub fn from_google_migration_payload(data: &[u8]) -> Result<Vec<OtpUri>, OtpError> {
+        let mut entries = Vec::new();
+
+        let mut reader = Reader { data };
+        while let Some((field, value)) = reader.next_field()? {
+            if let (PAYLOAD_OTP_PARAMETERS, Value::Bytes(parameters)) = (field, value) {
+                entries.push(parse_parameters(parameters)?);
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+fn parse_parameters(data: &[u8]) -> Result<OtpUri, OtpError> {
+    let mut secret = None;
+    let mut name = String::new();
+    let mut issuer = None;
+    let mut algorithm = HOTPAlgorithm::HMACSHA1;
+    let mut digits = Digits::SIX;
+    let mut otp_type = OTP_TYPE_TOTP;
+    let mut counter = 0;
+
+    let mut reader = Reader { data };
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (PARAMETERS_SECRET, Value::Bytes(v)) => secret = Some(Vec::from(v)),
+            (PARAMETERS_NAME, Value::Bytes(v)) => name = parse_string(v)?,
+            (PARAMETERS_ISSUER, Value::Bytes(v)) => issuer = Some(parse_string(v)?).filter(|v| !v.is_empty()),
+            (PARAMETERS_ALGORITHM, Value::Varint(v)) => {
+                algorithm = match v {
+                    0 | 1 => HOTPAlgorithm::HMACSHA1,
+                    2 => HOTPAlgorithm::HMACSHA256,
+                    3 => HOTPAlgorithm::HMACSHA512,
+                    _ => {
+                        return Err(OtpError::UnknownAlgorithm);
+                    }
+                };
+            },
+            (PARAMETERS_DIGITS, Value::Varint(v)) => {
+                digits = match v {
+                    0 | 1 => Digits::SIX,
+                    2 => Digits::EIGHT,
+                    _ => {
+                        return Err(OtpError::InvalidDigits);
+                    }
+                };
+            },
+            (PARAMETERS_TYPE, Value::Varint(v)) => otp_type = v,
+            (PARAMETERS_COUNTER, Value::Varint(v)) => counter = v,
+            _ => {},
+        }
+    }
+
+    let secret = match secret {
+        Some(secret) if !secret.is_empty() => secret,
+        _ => {
+            return Err(OtpError::EmptySecret);
+        }
+    };
+    let kind = match otp_type {
+        OTP_TYPE_HOTP => OtpUriKind::Hotp { counter },
+        OTP_TYPE_TOTP => OtpUriKind::Totp { period: DEFAULT_TIME_STEP },
+        _ => {
+            return Err(OtpError::InvalidMigrationPayload);
+        }
+    };
+
+    // Google Authenticator stores the label, which may repeat the issuer in front of the account.
+    let account = match &issuer {
+        Some(issuer) => match name.strip_prefix(issuer.as_str()).and_then(|v| v.strip_prefix(':')) {
+            Some(account) => String::from(account.trim_start()),
+            None => name,
+        },
+        None => name,
+    };
+
+    Ok(OtpUri {
+        kind,
+        secret: HOTP {
+            secret,
+            algorithm,
+        },
+        account,
+        issuer,
+        digits,
+        image: None,
+    })
+}
+
+fn parse_string(data: &[u8]) -> Result<String, OtpError> {
+    String::from_utf8(Vec::from(data)).map_err(|_| OtpError::InvalidMigrationPayload)
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn write_varint_field(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    write_varint(buffer, (field << 3) | WIRE_VARINT);
+    write_varint(buffer, value);
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, field: u64, value: &[u8]) {
+    write_varint(buffer, (field << 3) | WIRE_LENGTH_DELIMITED);
+    write_varint(buffer, value.len() as u64);
+    buffer.extend_from_slice(value);
+}
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Minimal protobuf reader, supporting the wire types needed to walk the migration messages.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn next_field(&mut self) -> Result<Option<(u64, Value<'a>)>, OtpError> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        let key = self.read_varint()?;
+        let value = match key & 0x7 {
+            WIRE_VARINT => Value::Varint(self.read_varint()?),
+            WIRE_FIXED64 => {
+                self.take(8)?;
+                Value::Fixed
+            },
+            WIRE_LENGTH_DELIMITED => {
+                let length = usize::try_from(self.read_varint()?).map_err(|_| OtpError::InvalidMigrationPayload)?;
+                Value::Bytes(self.take(length)?)
+            },
+            WIRE_FIXED32 => {
+                self.take(4)?;
+                Value::Fixed
+            },
+            _ => {
+                return Err(OtpError::InvalidMigrationPayload);
+            }
+        };
+
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn read_varint(&mut self) -> Result<u64, OtpError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.take(1)?.first().ok_or(OtpError::InvalidMigrationPayload)?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(OtpError::InvalidMigrationPayload)
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], OtpError> {
+        if self.data.len() < length {
+            return Err(OtpError::InvalidMigrationPayload);
+        }
+        let (taken, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(taken)
+    }
+}
@@ -0,0 +1,84 @@
+use super::*;
+
+/// Amount of characters between the hyphens of a formatted recovery code.
+const RECOVERY_GROUP_LENGTH: usize = 4;
+
+/// Prefix of the HMAC message, keeping recovery code hashes apart from OTP values.
+const RECOVERY_HASH_CONTEXT: &[u8] = b"libotp-recovery-code:";
+
+/// A one-time backup code, together with the hash the server should store instead of the code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryCode {
+    /// The code to show to the user once, e.g. `"7K3F-Q9MZ-2XHD"`.
+    pub code: String,
+    /// HMAC of the normalized code under the HOTP secret, to check presented codes with `HOTP::verify_recovery_code`.
+    pub hash: Vec<u8>,
+}
+
+impl HOTP {
+    /// Generates `count` random recovery codes of `code_length` characters each.
+    ///
+    /// Codes use Crockford's base32 alphabet and are grouped by four characters with hyphens.
+    /// Each code comes with an HMAC hash under this secret, so the codes do not have to be stored in plaintext.
+    /// Marking a code as used is left to the caller.
+    ///
+    /// # Arguments
+    /// * `count` - Amount of codes to generate.
+    /// * `code_length` - Amount of characters of each code, not counting the hyphens.
+    pub fn generate_recovery_codes(&self, count: usize, code_length: usize) -> Result<Vec<RecoveryCode>, OtpError> {
+        let mut codes = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            let random = match HOTP::generate_secret(code_length) {
+                Ok(v) => v,
+                Err(_) => {
+                    return Err(OtpError::RngError);
+                }
+            };
+            let symbols: Vec<u8> = random.iter().map(|b| b & 0x1f).collect();
+
+            let mut code = String::with_capacity(code_length + code_length / RECOVERY_GROUP_LENGTH);
+            for (i, symbol) in symbols.iter().enumerate() {
+                if i > 0 && i % RECOVERY_GROUP_LENGTH == 0 {
+                    code.push('-');
+                }
+                code.push(utils::CROCKFORD_ALPHABET[*symbol as usize] as char);
+            }
+
+            codes.push(RecoveryCode {
+                code,
+                hash: self.recovery_code_hash(&symbols),
+            });
+        }
+
+        Ok(codes)
+    }
+
+    /// Checks a presented recovery code against a hash returned by `generate_recovery_codes`.
+    ///
+    /// Hyphens and case are ignored, as well as the usual Crockford confusions (`O` for `0`, `I` and `L` for `1`).
+    ///
+    /// # Arguments
+    /// * `presented` - The recovery code entered by the user.
+    /// * `stored_hash` - The hash stored for the code.
+    pub fn verify_recovery_code(&self, presented: &str, stored_hash: &[u8]) -> bool {
+        let symbols: Option<Vec<u8>> = presented.bytes()
+            .filter(|c| *c != b'-')
+            .map(|c| utils::crockford_symbol(c).filter(|v| *v < 32))
+            .collect();
+
+        match symbols {
+            Some(symbols) if !symbols.is_empty() => {
+                utils::constant_time_eq(&self.recovery_code_hash(&symbols), stored_hash)
+            },
+            _ => false,
+        }
+    }
+
+    fn recovery_code_hash(&self, symbols: &[u8]) -> Vec<u8> {
+        let mut message = Vec::with_capacity(RECOVERY_HASH_CONTEXT.len() + symbols.len());
+        message.extend_from_slice(RECOVERY_HASH_CONTEXT);
+        message.extend_from_slice(symbols);
+        self.hmac_raw(&message)
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+use alloc::collections::BTreeSet;
+
+/// Storage of used `(counter, code)` pairs, to reject OTPs that were already accepted once.
+///
+/// Implement it to keep used codes in a shared store, such as a database, when several servers verify codes.
+pub trait ReplayProtection {
+    /// Returns `true` if `code` was already used for the time frame or counter `counter`.
+    fn is_replay(&self, counter: u64, code: u32) -> bool;
+
+    /// Records that `code` was used for `counter`.
+    fn record_use(&mut self, counter: u64, code: u32);
+
+    /// Forgets the codes of every counter before `oldest_valid_counter`, as they can't be accepted anymore.
+    fn evict_old(&mut self, oldest_valid_counter: u64);
+}
+
+/// In memory `ReplayProtection`, for a single process verifying codes.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReplayProtection {
+    used: BTreeSet<(u64, u32)>,
+}
+
+impl MemoryReplayProtection {
+    /// Creates an empty store.
+    pub fn new() -> MemoryReplayProtection {
+        MemoryReplayProtection::default()
+    }
+
+    /// Returns the amount of codes currently remembered.
+    pub fn len(&self) -> usize {
+        self.used.len()
+    }
+
+    /// Returns `true` if no code is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.used.is_empty()
+    }
+}
+
+impl ReplayProtection for MemoryReplayProtection {
+    fn is_replay(&self, counter: u64, code: u32) -> bool {
+        self.used.contains(&(counter, code))
+    }
+
+    fn record_use(&mut self, counter: u64, code: u32) {
+        self.used.insert((counter, code));
+    }
+
+    fn evict_old(&mut self, oldest_valid_counter: u64) {
+        self.used = self.used.split_off(&(oldest_valid_counter, 0));
+    }
+}
+
+/// Remembers accepted TOTP codes, so each code can only be used once.
+///
+/// A TOTP stays valid for its whole time frame, and for the frames around it when verifying with a window.
+/// The cache records every accepted `(counter, code)` pair and rejects it the next time, then forgets it
+/// once the time frame is too old to be accepted again.
+#[derive(Debug, Clone, Default)]
+pub struct UsedOtpCache {
+    window_size: u32,
+    latest_counter: u64,
+    used: MemoryReplayProtection,
+}
+
+impl UsedOtpCache {
+    /// Creates an empty cache.
+    ///
+    /// # Arguments
+    /// * `window_size` - Amount of frames to accept before and after the current one (0=Only current, 1=Previous+Now+Next, etc...)
+    pub fn new(window_size: u32) -> UsedOtpCache {
+        UsedOtpCache {
+            window_size,
+            latest_counter: 0,
+            used: MemoryReplayProtection::new(),
+        }
+    }
+
+    /// Verifies a code against `totp` and records it, reporting codes that were already accepted as
+    /// `OtpValidationResult::ReplayDetected`.
+    ///
+    /// Expired codes are evicted on every call, so the cache never holds more than the codes of one window.
+    ///
+    /// # Arguments
+    /// * `totp` - The TOTP to verify the code with, should be the same for every call.
+    /// * `code` - The user provided code to verify.
+    /// * `digits` - The amount of digits set for the OTP.
+    pub fn record_and_check<T: TimeProvider>(&mut self, totp: &TOTP<T>, code: u32, digits: u32) -> OtpValidationResult {
+        let now = match totp.clock.unix_seconds() {
+            Ok(now) => now,
+            Err(err) => {
+                return OtpValidationResult::SecretError(err);
+            }
+        };
+        if let Err(err) = Digits::try_new(digits) {
+            return OtpValidationResult::SecretError(err);
+        }
+
+        let counter = totp.counter_at(now);
+        let window_offset = match totp.verify_with_window_at_counter(code, digits, self.window_size, counter) {
+            TotpVerificationResult::Accepted { window_offset } => window_offset,
+            TotpVerificationResult::Rejected => {
+                return OtpValidationResult::InvalidCode;
+            }
+        };
+
+        self.latest_counter = self.latest_counter.max(counter);
+        self.evict_expired();
+
+        let counter_value = ((counter as i64) + (window_offset as i64)) as u64;
+        if self.used.is_replay(counter_value, code) {
+            return OtpValidationResult::ReplayDetected;
+        }
+        self.used.record_use(counter_value, code);
+
+        OtpValidationResult::Valid { window_offset, counter_value }
+    }
+
+    /// Forgets the codes of time frames that fell out of the window, relative to the latest verification.
+    pub fn evict_expired(&mut self) {
+        self.used.evict_old(self.latest_counter.saturating_sub(self.window_size as u64));
+    }
+
+    /// Returns the amount of codes currently remembered.
+    pub fn len(&self) -> usize {
+        self.used.len()
+    }
+
+    /// Returns `true` if no code is remembered.
+    pub fn is_empty(&self) -> bool {
+        self.used.is_empty()
+    }
+}
@@ -0,0 +1,135 @@
+//! `serde` support, secrets are stored base32 encoded, algorithms by their otpauth URI names and `OtpUri` as URI strings.
+
+use super::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+
+impl Serialize for HOTPAlgorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HOTPAlgorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(|_| D::Error::unknown_variant(&name, &["SHA1", "SHA256", "SHA512", "SHA3-256", "SHA3-512"]))
+    }
+}
+
+impl Serialize for HOTP {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("HOTP", 2)?;
+        state.serialize_field("secret", &self.get_secret_base32())?;
+        state.serialize_field("algorithm", &self.algorithm)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "HOTP")]
+struct HOTPFields {
+    secret: String,
+    algorithm: HOTPAlgorithm,
+}
+
+impl<'de> Deserialize<'de> for HOTP {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = HOTPFields::deserialize(deserializer)?;
+        HOTP::from_base32_predefined_algorithm(&fields.secret, fields.algorithm).map_err(D::Error::custom)
+    }
+}
+
+impl<T> Serialize for TOTP<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TOTP", 5)?;
+        state.serialize_field("secret", &self.secret)?;
+        state.serialize_field("time_step", &self.time_step)?;
+        state.serialize_field("start_time", &self.start_time)?;
+        match self.issuer {
+            Some(ref issuer) => state.serialize_field("issuer", issuer)?,
+            None => state.skip_field("issuer")?,
+        }
+        match self.account_name {
+            Some(ref account_name) => state.serialize_field("account_name", account_name)?,
+            None => state.skip_field("account_name")?,
+        }
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "TOTP")]
+struct TOTPFields {
+    secret: HOTP,
+    time_step: u64,
+    start_time: u64,
+    #[serde(default)]
+    issuer: Option<String>,
+    #[serde(default)]
+    account_name: Option<String>,
+}
+
+impl<'de, T: TimeProvider + Default> Deserialize<'de> for TOTP<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = TOTPFields::deserialize(deserializer)?;
+        let time_step = match TimeStep::new(fields.time_step) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(D::Error::custom("time_step must be greater than 0"));
+            }
+        };
+
+        let mut totp = TOTP::new_with_clock(fields.secret, time_step, fields.start_time, T::default());
+        totp.issuer = fields.issuer;
+        totp.account_name = fields.account_name;
+        Ok(totp)
+    }
+}
+
+impl Serialize for OtpUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OtpUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        uri.parse().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for OtpEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("OtpEntry", 3)?;
+        state.serialize_field("uri", &self.uri)?;
+        state.serialize_field("display_name", &self.display_name)?;
+        match self.last_used {
+            Some(ref last_used) => state.serialize_field("last_used", last_used)?,
+            None => state.skip_field("last_used")?,
+        }
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "OtpEntry")]
+struct OtpEntryFields {
+    uri: OtpUri,
+    display_name: String,
+    #[serde(default)]
+    last_used: Option<u64>,
+}
+
+impl<'de> Deserialize<'de> for OtpEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = OtpEntryFields::deserialize(deserializer)?;
+        Ok(OtpEntry {
+            uri: fields.uri,
+            display_name: fields.display_name,
+            last_used: fields.last_used,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Splitting secrets between several custodians with Shamir's Secret Sharing.
+
+use super::*;
+
+/// Length of the random identifier shared by all shares of one split.
+const SPLIT_ID_LEN: usize = 8;
+
+/// One share of a secret split with `HOTP::split_threshold`.
+///
+/// A share alone reveals nothing about the secret, `threshold` shares of the same split recombine it.
+/// Every share carries a random identifier of its split, so shares of different splits are not mixed up.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretShare {
+    threshold: u8,
+    split_id: [u8; SPLIT_ID_LEN],
+    share: Vec<u8>,
+}
+
+impl SecretShare {
+    /// Returns the index of this share, from 1 to the amount of shares.
+    pub fn index(&self) -> u8 {
+        self.share[0]
+    }
+
+    /// Returns the amount of shares required to recombine the secret.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Exports the share as bytes, to hand it to its custodian: the threshold, the split identifier, the index and
+    /// the share value.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + SPLIT_ID_LEN + self.share.len());
+        bytes.push(self.threshold);
+        bytes.extend_from_slice(&self.split_id);
+        bytes.extend_from_slice(&self.share);
+        bytes
+    }
+
+    /// Loads a share exported by `to_bytes`.
+    ///
+    /// # Arguments
+    /// * `data` - The exported share.
+    pub fn from_bytes(data: &[u8]) -> Result<SecretShare, OtpError> {
+        if data.len() < 1 + SPLIT_ID_LEN + 2 || data[0] == 0 || data[1 + SPLIT_ID_LEN] == 0 {
+            return Err(OtpError::InvalidShare);
+        }
+
+        Ok(SecretShare {
+            threshold: data[0],
+            split_id: data[1..1 + SPLIT_ID_LEN].try_into().expect("slice is SPLIT_ID_LEN bytes long"),
+            share: Vec::from(&data[1 + SPLIT_ID_LEN..]),
+        })
+    }
+}
+
+impl core::fmt::Debug for SecretShare {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index())
+            .field("share", &format_args!("[REDACTED {} bytes]", self.share.len() - 1))
+            .finish()
+    }
+}
+
+/// Wipes the share from memory once it is no longer used.
+#[cfg(feature = "zeroize")]
+impl Drop for SecretShare {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        self.share.zeroize();
+    }
+}
+
+impl HOTP {
+    /// Splits the secret into `shares` shares, any `threshold` of which recombine it with `HOTP::recombine`.
+    ///
+    /// Fewer than `threshold` shares reveal nothing about the secret, so no single custodian holds it.
+    /// Returns `OtpError::InvalidThreshold` if `threshold` is 0 or greater than `shares`, and `OtpError::RngError` if
+    /// the split identifier can't be generated.
+    ///
+    /// # Arguments
+    /// * `shares` - Amount of shares to create, one per custodian.
+    /// * `threshold` - Amount of shares required to recombine the secret.
+    pub fn split_threshold(&self, shares: u8, threshold: u8) -> Result<Vec<SecretShare>, OtpError> {
+        if threshold == 0 || threshold > shares {
+            return Err(OtpError::InvalidThreshold);
+        }
+
+        let split_id = match HOTP::generate_secret(SPLIT_ID_LEN) {
+            Ok(v) => v.try_into().expect("secret is SPLIT_ID_LEN bytes long"),
+            Err(_) => {
+                return Err(OtpError::RngError);
+            }
+        };
+
+        Ok(sharks::Sharks(threshold).dealer(&self.secret).take(shares as usize).map(|share| SecretShare {
+            threshold,
+            split_id,
+            share: Vec::from(&share),
+        }).collect())
+    }
+
+    /// Recombines a secret from the shares created by `split_threshold`.
+    ///
+    /// Returns `OtpError::InsufficientShares` if there are fewer distinct shares than the threshold,
+    /// `OtpError::InvalidShare` if the shares don't belong to the same split, and `OtpError::SecretTooShort` if the
+    /// recombined secret is shorter than 16 bytes.
+    ///
+    /// # Arguments
+    /// * `shares` - At least `threshold` shares of the secret.
+    /// * `algorithm` - Algorithm to use for OTP generation.
+    pub fn recombine(shares: &[SecretShare], algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+        let first = shares.first().ok_or(OtpError::InsufficientShares)?;
+        if shares.iter().any(|share| {
+            share.threshold != first.threshold || share.split_id != first.split_id || share.share.len() != first.share.len()
+        }) {
+            return Err(OtpError::InvalidShare);
+        }
+
+        let parsed = shares.iter()
+            .map(|share| sharks::Share::try_from(share.share.as_slice()).map_err(|_| OtpError::InvalidShare))
+            .collect::<Result<Vec<_>, OtpError>>()?;
+        let secret = sharks::Sharks(first.threshold).recover(&parsed).map_err(|_| OtpError::InsufficientShares)?;
+
+        HOTP::with_valid_length(secret, algorithm)
+    }
+}
@@ -0,0 +1,71 @@
+use super::*;
+
+/// Alphabet used by the Steam Guard mobile authenticator.
+const STEAM_ALPHABET: &[u8; 26] = b"23456789BCDFGHJKMNPQRTVWXY";
+
+/// Length of a Steam Guard code in characters.
+const STEAM_CODE_LENGTH: usize = 5;
+
+/// Steam Guard compatible TOTP, producing 5 character alphanumeric codes.
+///
+/// Steam uses HMAC-SHA1 with a 30 seconds time step, but encodes the truncated HOTP value
+/// with its own alphabet instead of decimal digits.
+#[derive(Clone, Debug)]
+pub struct SteamGuardTotp<T = SystemTimeClock> {
+    totp: TOTP<T>,
+}
+
+#[cfg(feature = "std")]
+impl SteamGuardTotp {
+    /// Creates a Steam Guard TOTP using the system clock.
+    ///
+    /// # Arguments
+    /// * `secret` - The Steam shared secret, always used with HMAC-SHA1.
+    pub fn new(secret: HOTP) -> SteamGuardTotp {
+        SteamGuardTotp::new_with_clock(secret, SystemTimeClock)
+    }
+}
+
+impl<T: TimeProvider> SteamGuardTotp<T> {
+    /// Creates a Steam Guard TOTP reading the current time from `clock`.
+    ///
+    /// # Arguments
+    /// * `secret` - The Steam shared secret, always used with HMAC-SHA1.
+    /// * `clock` - The time source used to compute the current time frame.
+    pub fn new_with_clock(mut secret: HOTP, clock: T) -> SteamGuardTotp<T> {
+        secret.algorithm = HOTPAlgorithm::HMACSHA1;
+
+        SteamGuardTotp {
+            totp: TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, clock),
+        }
+    }
+
+    /// Generates a Steam Guard code.
+    ///
+    /// # Arguments
+    /// * `offset` - Should be 0 for current time frame, -1 for previous, 1 for next, etc...
+    pub fn get_steam_otp(&self, offset: i32) -> String {
+        let counter = ((self.totp.current_counter() as i64) + (offset as i64)) as u64;
+        self.get_steam_otp_for_counter(counter)
+    }
+
+    /// Generates the Steam Guard code that is valid at the given Unix time.
+    ///
+    /// # Arguments
+    /// * `unix_seconds` - Seconds since the Unix epoch.
+    pub fn get_steam_otp_at(&self, unix_seconds: u64) -> String {
+        self.get_steam_otp_for_counter(self.totp.counter_at(unix_seconds))
+    }
+
+    fn get_steam_otp_for_counter(&self, counter: u64) -> String {
+        let hmac = self.totp.secret.hmac_raw(&utils::num_to_buffer(counter));
+        let mut value = HOTP::get_hotp_value(&hmac) as usize;
+
+        let mut code = String::with_capacity(STEAM_CODE_LENGTH);
+        for _ in 0..STEAM_CODE_LENGTH {
+            code.push(STEAM_ALPHABET[value % STEAM_ALPHABET.len()] as char);
+            value /= STEAM_ALPHABET.len();
+        }
+        code
+    }
+}
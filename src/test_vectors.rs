@@ -0,0 +1,25 @@
+//! The shared secrets used by the test vectors of RFC4226 and RFC6238, for compliance smoke tests.
+
+use super::*;
+
+impl HOTP {
+    /// Returns the HMAC-SHA1 secret `"12345678901234567890"` of [RFC4226 appendix D](https://tools.ietf.org/html/rfc4226#appendix-D).
+    pub fn rfc4226_test_secret() -> HOTP {
+        HOTP::from_bin_unchecked(b"12345678901234567890")
+    }
+
+    /// Returns the 20 byte HMAC-SHA1 secret of [RFC6238 appendix B](https://tools.ietf.org/html/rfc6238#appendix-B).
+    pub fn rfc6238_sha1_secret() -> HOTP {
+        HOTP::from_bin_unchecked(b"12345678901234567890")
+    }
+
+    /// Returns the 32 byte HMAC-SHA256 secret of [RFC6238 appendix B](https://tools.ietf.org/html/rfc6238#appendix-B).
+    pub fn rfc6238_sha256_secret() -> HOTP {
+        HOTP::from_bin_unchecked(b"12345678901234567890123456789012")
+    }
+
+    /// Returns the 64 byte HMAC-SHA512 secret of [RFC6238 appendix B](https://tools.ietf.org/html/rfc6238#appendix-B).
+    pub fn rfc6238_sha512_secret() -> HOTP {
+        HOTP::from_bin_unchecked(b"1234567890123456789012345678901234567890123456789012345678901234")
+    }
+}
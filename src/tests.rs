@@ -1,5 +1,10 @@
 use super::*;
 
+static_assertions::assert_impl_all!(HOTP: Send, Sync);
+static_assertions::assert_impl_all!(HOTPCounter: Send, Sync);
+static_assertions::assert_impl_all!(TOTP: Send, Sync);
+static_assertions::assert_impl_all!(TOTP<FixedClock>: Send, Sync);
+
 #[test]
 fn test_gen_secret() {
     let hotp_sha1 = HOTP::new(HOTPAlgorithm::HMACSHA1).unwrap();
@@ -73,24 +78,24 @@ fn test_secret() {
         algorithm: HOTPAlgorithm::HMACSHA512,
     };
 
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], 8), 94287082);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], 8), 46119246);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], 8), 90693936);
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], 8), 7081804);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], 8), 68084774);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], 8), 25091201);
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], 8), 14050471);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], 8), 67062674);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], 8), 99943326);
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], 8), 89005924);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], 8), 91819424);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], 8), 93441116);
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], 8), 69279037);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], 8), 90698825);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], 8), 38618901);
-    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], 8), 65353130);
-    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], 8), 77737706);
-    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], 8), 47863826);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], Digits::EIGHT), 94287082);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], Digits::EIGHT), 46119246);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0, 0, 0, 1], Digits::EIGHT), 90693936);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], Digits::EIGHT), 7081804);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], Digits::EIGHT), 68084774);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xec], Digits::EIGHT), 25091201);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], Digits::EIGHT), 14050471);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], Digits::EIGHT), 67062674);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x35, 0x23, 0xed], Digits::EIGHT), 99943326);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], Digits::EIGHT), 89005924);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], Digits::EIGHT), 91819424);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x02, 0x73, 0xef, 0x07], Digits::EIGHT), 93441116);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], Digits::EIGHT), 69279037);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], Digits::EIGHT), 90698825);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x03, 0xf9, 0x40, 0xaa], Digits::EIGHT), 38618901);
+    assert_eq!(hotp_sha1.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], Digits::EIGHT), 65353130);
+    assert_eq!(hotp_sha256.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], Digits::EIGHT), 77737706);
+    assert_eq!(hotp_sha512.get_otp(&[0, 0, 0, 0, 0x27, 0xbc, 0x86, 0xaa], Digits::EIGHT), 47863826);
 }
 
 #[test]
@@ -106,8 +111,1377 @@ fn test_time_to_counter() {
 
 #[test]
 fn test_totp_prefix() {
-    assert_eq!(totp_u23_to_string(012345, 6), "012345");
+    assert_eq!(totp_u23_to_string(12345, 6), "012345");
     assert_eq!(totp_u23_to_string(456789, 6), "456789");
     assert_eq!(totp_u23_to_string(456, 6), "000456");
-    assert_eq!(totp_u23_to_string(08976423, 8), "08976423");
+    assert_eq!(totp_u23_to_string(8976423, 8), "08976423");
+}
+
+#[test]
+fn test_hotp_from_invalid_base32() {
+    assert_eq!(HOTP::from_base32("NOT-BASE32!").err(), Some(OtpError::InvalidBase32));
+    assert_eq!(HOTP::from_base32_predefined_algorithm("NOT-BASE32!", HOTPAlgorithm::HMACSHA256).err(), Some(OtpError::InvalidBase32));
+}
+
+#[test]
+fn test_get_otp_string() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(hotp.get_otp_string(&utils::num_to_buffer(0), 6), "755224");
+    assert_eq!(hotp.get_otp_string(&utils::num_to_buffer(1111111109 / 30), 8), "07081804");
+    assert_eq!(hotp.get_otp_string(&utils::num_to_buffer(0), 0), "");
+    assert_eq!(hotp.get_otp_string_for_counter(1111111109 / 30, 8), "07081804");
+    assert_eq!(hotp.get_otp_as_bytes(&utils::num_to_buffer(0), 6), 755224u32.to_be_bytes());
+    assert_eq!(hotp.get_otp_as_bytes(&utils::num_to_buffer(1111111109 / 30), 8), [0x00, 0x6c, 0x0f, 0x4c]);
+    assert_eq!(hotp.get_otp_as_le_bytes(&utils::num_to_buffer(1111111109 / 30), 8), [0x4c, 0x0f, 0x6c, 0x00]);
+
+    let totp = TOTP::new(hotp, TimeStep::THIRTY_SECONDS, 0);
+    assert_eq!(totp.get_otp_string(6, 0).len(), 6);
+}
+
+#[test]
+fn test_totp_verify() {
+    // RFC6238 appendix B, T = 59 is in the second time frame
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(59));
+    assert!(totp.verify(287082, 6));
+    assert!(!totp.verify(755224, 6));
+    assert!(!totp.verify(287082 + 1_000_000, 6));
+}
+
+#[test]
+fn test_totp_verify_with_window() {
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::new(3600).unwrap(), 0);
+    let previous = totp.get_otp(8, -2);
+
+    assert_eq!(totp.verify_with_window(previous, 8, 2), TotpVerificationResult::Accepted { window_offset: -2 });
+    assert_eq!(totp.verify_with_window(previous, 8, 1), TotpVerificationResult::Rejected);
+    assert!(!totp.verify_with_window(previous, 8, 1).is_accepted());
+}
+
+#[test]
+fn test_otp_comparator() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111));
+    let comparator = OtpComparator::build(&totp, 8, 1);
+
+    assert_eq!(comparator.compare(14050471), Some(0));
+    assert_eq!(comparator.compare(7081804), Some(-1));
+    assert_eq!(comparator.compare(totp.get_otp(8, 1)), Some(1));
+    assert_eq!(comparator.compare(12345678), None);
+    assert_eq!(OtpComparator::build(&totp, 8, 0).compare(7081804), None);
+}
+
+#[test]
+fn test_totp_fractional_window() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111125));
+    assert_eq!(totp.get_fractional_window(), 37037037.5);
+    assert_eq!(totp.get_otp_at_fractional(totp.get_fractional_window(), 8), 14050471);
+    assert_eq!(totp.get_otp_at_fractional(37037036.99, 8), 7081804);
+    assert_eq!(totp.get_otp_at_fractional(-1.5, 8), totp.get_otp_at(0, 8));
+}
+
+#[test]
+fn test_rfc_compliance_warnings() {
+    let sha256 = HOTP::from_bin(b"12345678901234567890123456789012").unwrap();
+    assert_eq!(sha256.validate_rfc4226(), Ok(()));
+    assert_eq!(HOTP::from_bin_unchecked(b"1234567890").validate_rfc4226(), Err(vec!["secret is shorter than 128 bits"]));
+    let sha3 = sha256.clone_with_new_algorithm(HOTPAlgorithm::HMACSHA3_256);
+    assert_eq!(sha3.validate_rfc4226(), Err(vec!["SHA3 algorithms are not covered by RFC4226 or RFC6238"]));
+
+    assert_eq!(TOTP::new(sha256.clone(), TimeStep::THIRTY_SECONDS, 0).validate_rfc6238(), Ok(()));
+    assert_eq!(TOTP::new(sha256, TimeStep::new(60).unwrap(), 0).validate_rfc6238(), Ok(()));
+    assert_eq!(TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::new(45).unwrap(), 10).validate_rfc6238(), Err(vec![
+        "time step is neither 30 nor 60 seconds",
+        "start time is not the Unix epoch",
+        "SHA-1 is deprecated",
+    ]));
+}
+
+#[test]
+fn test_totp_multiple_digits() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111));
+    assert_eq!(totp.get_otp_multiple_digits(&[6, 8], 0), vec![(6, 50471), (8, 14050471)]);
+    assert_eq!(totp.get_otp_multiple_digits(&[8, 6], -1), vec![(8, 7081804), (6, 81804)]);
+    assert_eq!(totp.get_otp_multiple_digits(&[], 0), vec![]);
+}
+
+#[test]
+fn test_totp_serialize_config() {
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::new(60).unwrap(), 100);
+    let data = totp.serialize_config();
+    assert_eq!(&data[..6], b"\x01TOTP\x01");
+    assert_eq!(data.len(), 26 + 20);
+
+    let loaded = TOTP::deserialize_config(&data).unwrap();
+    assert_eq!(loaded.get_otp_at(1111111111, 8), totp.get_otp_at(1111111111, 8));
+    assert_eq!(loaded.serialize_config(), data);
+
+    let mut wrong_version = data.clone();
+    wrong_version[0] = 2;
+    assert_eq!(TOTP::deserialize_config(&wrong_version).err(), Some(OtpError::InvalidConfig));
+    assert_eq!(TOTP::deserialize_config(&data[..data.len() - 1]).err(), Some(OtpError::InvalidConfig));
+    assert_eq!(TOTP::deserialize_config(b"HOTP").err(), Some(OtpError::InvalidConfig));
+    let mut zero_step = data.clone();
+    zero_step[6] = 0;
+    assert_eq!(TOTP::deserialize_config(&zero_step).err(), Some(OtpError::InvalidTimeStep));
+
+    let short = TOTP::new(HOTP::from_bin_unchecked(b"too short"), TimeStep::new(60).unwrap(), 100).serialize_config();
+    assert_eq!(TOTP::deserialize_config(&short).err(), Some(OtpError::SecretTooShort { actual: 9, minimum: 16 }));
+}
+
+#[test]
+fn test_hotp_counter() {
+    let mut counter = HOTPCounter::new(HOTP::from_bin(b"12345678901234567890").unwrap(), 0);
+    assert_eq!(counter.peek_next_otp(6), 755224);
+    assert_eq!(counter.next_otp(6), 755224);
+    assert_eq!(counter.next_otp(6), 287082);
+    assert_eq!(counter.current_counter(), 2);
+
+    let checkpoint = counter.clone();
+    counter.set_counter(9);
+    assert_eq!(counter.next_otp(6), 520489);
+    assert_eq!(checkpoint.current_counter(), 2);
+}
+
+#[test]
+fn test_hotp_counter_sync() {
+    let mut counter = HOTPCounter::new(HOTP::from_bin(b"12345678901234567890").unwrap(), 2);
+    assert_eq!(counter.sync(254676, 6, 2), Err(OtpError::SyncFailed));
+    assert_eq!(counter.current_counter(), 2);
+
+    assert_eq!(counter.sync(254676, 6, 3), Ok(5));
+    assert_eq!(counter.current_counter(), 6);
+    assert_eq!(counter.next_otp(6), 287922);
+
+    let mut counter = HOTPCounter::new(HOTP::from_bin(b"12345678901234567890").unwrap(), 2);
+    assert_eq!(counter.verify_with_lookahead(969429, 6, 1), OtpValidationResult::Valid { window_offset: 1, counter_value: 3 });
+    assert_eq!(counter.verify_with_lookahead(969429, 6, 1), OtpValidationResult::ReplayDetected);
+    assert_eq!(counter.verify_with_lookahead(755224, 6, 1), OtpValidationResult::InvalidCode);
+    assert_eq!(counter.verify_with_lookahead(338314, 6, 1), OtpValidationResult::Valid { window_offset: 0, counter_value: 4 });
+    assert_eq!(counter.verify_with_lookahead(338314, 0, 1), OtpValidationResult::SecretError(OtpError::InvalidDigits));
+
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let (second_last, last) = (secret.get_otp_counter(u64::MAX - 1, 6), secret.get_otp_counter(u64::MAX, 6));
+    assert_ne!(second_last, last);
+    let mut counter = HOTPCounter::new(secret, u64::MAX - 2);
+    assert_eq!(counter.verify_with_lookahead(second_last, 6, u32::MAX), OtpValidationResult::Valid { window_offset: 1, counter_value: u64::MAX - 1 });
+    assert_eq!(counter.verify_with_lookahead(second_last, 6, u32::MAX), OtpValidationResult::ReplayDetected);
+    assert_eq!(counter.verify_with_lookahead(last, 6, 0), OtpValidationResult::SecretError(OtpError::CounterExhausted));
+    assert_eq!(counter.verify_with_lookahead(last, 6, 0), OtpValidationResult::SecretError(OtpError::CounterExhausted));
+    assert_eq!(counter.current_counter(), u64::MAX);
+}
+
+#[test]
+fn test_used_otp_cache() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let now = TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111));
+    let later = TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111141));
+
+    let mut cache = UsedOtpCache::new(1);
+    assert_eq!(cache.record_and_check(&now, 14050471, 8), OtpValidationResult::Valid { window_offset: 0, counter_value: 37037037 });
+    assert_eq!(cache.record_and_check(&now, 14050471, 8), OtpValidationResult::ReplayDetected);
+    assert_eq!(cache.record_and_check(&now, 7081804, 8), OtpValidationResult::Valid { window_offset: -1, counter_value: 37037036 });
+    assert_eq!(cache.record_and_check(&now, 12345678, 8), OtpValidationResult::InvalidCode);
+    assert_eq!(cache.record_and_check(&now, 7081804, 0), OtpValidationResult::SecretError(OtpError::InvalidDigits));
+    assert_eq!(cache.len(), 2);
+
+    assert_eq!(cache.record_and_check(&later, 14050471, 8), OtpValidationResult::ReplayDetected);
+    assert_eq!(cache.len(), 1);
+    cache.evict_expired();
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_replay_protection() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let now = DriftTolerantTOTP::new(TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111)), 1);
+    let later = DriftTolerantTOTP::new(TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111141)), 1);
+
+    let mut rp = MemoryReplayProtection::new();
+    assert_eq!(now.verify_with_replay_protection(14050471, 8, &mut rp), TotpVerificationResult::Accepted { window_offset: 0 });
+    assert_eq!(now.verify_with_replay_protection(14050471, 8, &mut rp), TotpVerificationResult::Rejected);
+    assert_eq!(now.verify_with_replay_protection(7081804, 8, &mut rp), TotpVerificationResult::Accepted { window_offset: -1 });
+    assert_eq!(now.verify_with_replay_protection(12345678, 8, &mut rp), TotpVerificationResult::Rejected);
+    assert!(rp.is_replay(37037036, 7081804));
+    assert_eq!(rp.len(), 2);
+
+    assert_eq!(later.verify_with_replay_protection(14050471, 8, &mut rp), TotpVerificationResult::Rejected);
+    assert_eq!(later.verify_with_replay_protection(later.totp().get_otp(8, 0), 8, &mut rp), TotpVerificationResult::Accepted { window_offset: 0 });
+    assert!(!rp.is_replay(37037036, 7081804));
+    assert_eq!(rp.len(), 2);
+}
+
+#[test]
+fn test_otpauth_uri() {
+    let totp = TOTP::new(HOTP::from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    let uri = totp.to_otpauth_uri("alice@example.com", Some("Example Co"), Digits::SIX);
+    assert_eq!(uri, "otpauth://totp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA1&digits=6&period=30");
+
+    let (parsed, meta) = TOTP::from_otpauth_uri(&uri).unwrap();
+    assert_eq!(parsed.secret.secret, totp.secret.secret);
+    assert_eq!(parsed.time_step, 30);
+    assert_eq!(meta, OtpUriMeta {
+        account: String::from("alice@example.com"),
+        issuer: Some(String::from("Example Co")),
+        digits: Digits::SIX,
+        image: None,
+    });
+
+    assert_eq!(TOTP::from_otpauth_uri("otpauth://hotp/alice?secret=GEZDGNBV").err(), Some(OtpError::InvalidUri));
+    assert_eq!(TOTP::from_otpauth_uri("otpauth://totp/alice?issuer=Example").err(), Some(OtpError::InvalidUri));
+}
+
+#[test]
+fn test_keyuri_string() {
+    let totp = TOTP::new(HOTP::from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    assert_eq!(totp.to_keyuri_string("john doe", "ACME Co", Digits::SIX).unwrap(), "otpauth://totp/ACME%20Co:john%20doe?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co&algorithm=SHA1&digits=6&period=30");
+    assert_eq!(totp.to_keyuri_string("john:doe", "ACME Co", Digits::SIX), Err(OtpError::InvalidUri));
+    assert_eq!(totp.to_keyuri_string("john", "ACME:Co", Digits::SIX), Err(OtpError::InvalidUri));
+}
+
+#[test]
+fn test_from_keyuri_string() {
+    let totp = TOTP::new(HOTP::from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    let uri = totp.to_keyuri_string("john doe", "ACME Co", Digits::SIX).unwrap();
+    let (parsed, meta) = TOTP::from_keyuri_string(&uri).unwrap();
+    assert_eq!(parsed.get_otp_at(59, 6), totp.get_otp_at(59, 6));
+    assert_eq!(meta.account, "john doe");
+    assert_eq!(meta.issuer.as_deref(), Some("ACME Co"));
+    assert_eq!(meta.digits, Digits::SIX);
+
+    let (parsed, meta) = TOTP::from_keyuri_string("otpauth://totp/Label%20Co:alice?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&issuer=Param%20Co").unwrap();
+    assert_eq!(parsed.get_otp_at(59, 8), 94287082);
+    assert_eq!(meta.account, "alice");
+    assert_eq!(meta.issuer.as_deref(), Some("Param Co"));
+
+    let (unpadded, _) = TOTP::from_keyuri_string("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGE").unwrap();
+    let (padded, _) = TOTP::from_keyuri_string("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGE%3D%3D%3D%3D%3D%3D").unwrap();
+    assert_eq!(unpadded.get_otp_at(59, 8), padded.get_otp_at(59, 8));
+
+    let (counter, meta) = HOTPCounter::from_hotp_keyuri_string("otpauth://hotp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=5&digits=8").unwrap();
+    assert_eq!(counter.current_counter(), 5);
+    assert_eq!(meta.digits, Digits::EIGHT);
+    assert_eq!(meta.issuer, None);
+    assert_eq!(HOTPCounter::from_hotp_keyuri_string(&uri).err(), Some(OtpError::InvalidUri));
+}
+
+#[test]
+fn test_otp_uri() {
+    let uri = "otpauth://hotp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA256&digits=8&counter=5&image=https%3A%2F%2Fexample.com%2Flogo.png";
+    let parsed: OtpUri = uri.parse().unwrap();
+    assert_eq!(parsed.kind, OtpUriKind::Hotp { counter: 5 });
+    assert_eq!(parsed.secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(parsed.account, "alice@example.com");
+    assert_eq!(parsed.issuer.as_deref(), Some("Example Co"));
+    assert_eq!(parsed.digits, Digits::EIGHT);
+    assert_eq!(parsed.image.as_deref(), Some("https://example.com/logo.png"));
+    assert_eq!(parsed.to_string(), uri);
+
+    assert_eq!(parsed.to_hotp_counter().unwrap().current_counter(), 5);
+    assert_eq!(parsed.to_totp().err(), Some(OtpError::InvalidUri));
+    assert_eq!("otpauth://hotp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ".parse::<OtpUri>(), Err(OtpError::InvalidUri));
+    assert_eq!("otpauth://motp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ".parse::<OtpUri>(), Err(OtpError::InvalidUri));
+    assert_eq!("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&digits=0".parse::<OtpUri>(), Err(OtpError::InvalidUri));
+    assert_eq!("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&digits=11".parse::<OtpUri>(), Err(OtpError::InvalidUri));
+    assert_eq!("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&digits=10".parse::<OtpUri>().unwrap().digits.get(), 10);
+
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::SIXTY_SECONDS, 0);
+    let meta = OtpUriMeta { account: String::from("bob"), issuer: None, digits: Digits::SIX, image: None };
+    let totp_uri = totp.to_uri(&meta);
+    assert_eq!(totp_uri.to_string(), "otpauth://totp/bob?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&algorithm=SHA1&digits=6&period=60");
+    let round_trip = totp_uri.to_string().parse::<OtpUri>().unwrap().to_totp().unwrap();
+    assert_eq!(round_trip.time_step, 60);
+    assert_eq!(round_trip.account_name(), Some("bob"));
+    assert_eq!(round_trip.secret, totp.secret);
+}
+
+#[test]
+fn test_otp_vault() {
+    let mut vault = OtpVault::new();
+    assert!(vault.is_empty());
+    vault.add("github", OtpEntry::new("otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=GitHub".parse().unwrap(), "GitHub (work)"));
+    vault.add("bank", OtpEntry::new("otpauth://hotp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=1".parse().unwrap(), "Bank"));
+    assert_eq!(vault.len(), 2);
+    assert_eq!(vault.iter().map(|(name, _)| name).collect::<Vec<_>>(), vec!["bank", "github"]);
+    assert_eq!(vault.get("github").unwrap().issuer(), Some("GitHub"));
+    assert_eq!(vault.get("github").unwrap().display_name, "GitHub (work)");
+    assert_eq!(vault.get("bank").unwrap().last_used, None);
+
+    assert_eq!(vault.get_otp("bank", 6), Some(287082));
+    assert!(vault.get("bank").unwrap().last_used.is_some());
+    assert_eq!(vault.get("github").unwrap().last_used, None);
+    assert!(vault.get_otp("github", 6).unwrap() < 1_000_000);
+    assert!(vault.get("github").unwrap().last_used.is_some());
+    assert_eq!(vault.get_otp("github", 11), None);
+    assert_eq!(vault.get_otp("missing", 6), None);
+
+    #[cfg(feature = "serde")]
+    {
+        let json = vault.to_json().unwrap();
+        let loaded = OtpVault::from_json(&json).unwrap();
+        assert_eq!(loaded, vault);
+        assert_eq!(loaded.get("github").unwrap().display_name, "GitHub (work)");
+        assert_eq!(loaded.get("bank").unwrap().last_used, vault.get("bank").unwrap().last_used);
+
+        let never_used = OtpVault::from_json(r#"{"a": {"uri": "otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", "display_name": "A"}}"#).unwrap();
+        assert_eq!(never_used.get("a").unwrap().last_used, None);
+        assert_eq!(OtpVault::from_json(r#"{"a": {"uri": "otpauth://motp/alice", "display_name": "A"}}"#), Err(OtpError::InvalidVault));
+        assert_eq!(OtpVault::from_json("{\"a\": \"otpauth://totp/alice?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\"}"), Err(OtpError::InvalidVault));
+    }
+
+    assert!(vault.remove("bank").is_some());
+    assert!(vault.remove("bank").is_none());
+    assert_eq!(vault.len(), 1);
+}
+
+#[test]
+fn test_otpauth_uri_with_meta() {
+    let meta = OtpUriMeta {
+        account: String::from("alice@example.com"),
+        issuer: Some(String::from("Example Co")),
+        digits: Digits::EIGHT,
+        image: Some(String::from("https://example.com/logo.png")),
+    };
+
+    let totp = TOTP::new(HOTP::from_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), TimeStep::SIXTY_SECONDS, 0);
+    let uri = totp.to_otpauth_uri_with_meta(&meta);
+    assert_eq!(uri, "otpauth://totp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA1&digits=8&period=60&image=https%3A%2F%2Fexample.com%2Flogo.png");
+    assert_eq!(uri.parse::<OtpUri>().unwrap().meta(), meta);
+
+    let uri = totp.secret.to_otpauth_uri_with_meta(&meta, 7);
+    assert_eq!(uri, "otpauth://hotp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA1&digits=8&counter=7&image=https%3A%2F%2Fexample.com%2Flogo.png");
+    assert_eq!(uri.parse::<OtpUri>().unwrap().to_hotp_counter().unwrap().current_counter(), 7);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn test_hotp_zeroize() {
+    use zeroize::Zeroize;
+
+    let mut hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    hotp.zeroize();
+    assert!(hotp.secret.is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::SIXTY_SECONDS, 10);
+    let json = serde_json::to_string(&totp).unwrap();
+    assert_eq!(json, r#"{"secret":{"secret":"GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ","algorithm":"SHA1"},"time_step":60,"start_time":10}"#);
+
+    let parsed: TOTP = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.secret.secret, totp.secret.secret);
+    assert_eq!(parsed.time_step, 60);
+    assert_eq!(parsed.start_time, 10);
+
+    assert!(serde_json::from_str::<HOTPAlgorithm>(r#""MD5""#).is_err());
+    assert!(serde_json::from_str::<TOTP>(r#"{"secret":{"secret":"GEZDGNBV","algorithm":"SHA1"},"time_step":0,"start_time":0}"#).is_err());
+}
+
+#[test]
+fn test_totp_time_remaining() {
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 7);
+    let remaining = totp.time_remaining();
+    assert!((1..=30).contains(&remaining));
+    assert!(totp.time_elapsed() < 30);
+}
+
+#[test]
+fn test_otp_policy() {
+    assert_eq!(OtpPolicy::default(), OtpPolicy::new_standard());
+    assert_eq!(OtpPolicy::new_standard().digits, Digits::SIX);
+
+    let policy = OtpPolicy::new_high_security();
+    let secret = HOTP::from_bin(b"12345678901234567890123456789012").unwrap();
+    let sha1 = secret.clone_with_new_algorithm(HOTPAlgorithm::HMACSHA1);
+    assert_eq!(TOTP::new_with_policy(sha1, policy, 0).err(), Some(OtpError::AlgorithmMismatch));
+
+    let totp = TOTP::new_with_policy(secret, policy, 0).unwrap();
+    assert_eq!(totp.secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(totp.time_step, 30);
+    assert_eq!(totp.get_otp_at(59, policy.digits.get()), 46119246);
+}
+
+#[test]
+fn test_totp_new_with_random_secret() {
+    let totp = TOTP::new_with_random_secret(HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(totp.secret.len(), 20);
+    assert_eq!((totp.time_step, totp.start_time), (30, 0));
+    assert_ne!(totp.secret, TOTP::new_with_random_secret(HOTPAlgorithm::HMACSHA1).unwrap().secret);
+
+    let totp = TOTP::new_with_random_secret_and_policy(OtpPolicy::new_high_security()).unwrap();
+    assert_eq!(totp.secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(totp.secret.len(), 32);
+    assert_eq!(totp.time_step, 30);
+}
+
+#[test]
+fn test_totp_window_boundaries() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 7, FixedClock(100));
+    assert_eq!(totp.get_window_boundaries(), (97, 126));
+    assert_eq!(totp.get_window_boundaries_at(97), (97, 126));
+    assert_eq!(totp.get_window_boundaries_at(127), (127, 156));
+    assert_eq!(totp.get_window_boundaries_at(0), (7, 36));
+    assert_eq!(totp.current_window_start(), std::time::UNIX_EPOCH + std::time::Duration::from_secs(97));
+    assert_eq!(totp.next_window_at(), std::time::UNIX_EPOCH + std::time::Duration::from_secs(127));
+}
+
+#[test]
+fn test_totp_simulate_otp_sequence() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(0));
+    assert_eq!(totp.simulate_otp_sequence(1111111080, 1111111139, 8), vec![(1111111080, 7081804), (1111111110, 14050471)]);
+    assert_eq!(totp.simulate_otp_sequence(1111111081, 1111111139, 8), vec![(1111111110, 14050471)]);
+    assert_eq!(totp.simulate_otp_sequence(1111111080, 1111111138, 8), vec![(1111111080, 7081804)]);
+    assert_eq!(totp.simulate_otp_sequence(0, 29, 8), vec![(0, totp.get_otp_at(0, 8))]);
+    assert_eq!(totp.simulate_otp_sequence(10, 20, 8), vec![]);
+    assert_eq!(totp.simulate_otp_sequence(0, 3600, 6).len(), 120);
+}
+
+#[test]
+fn test_totp_otp_with_counter() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109));
+    assert_eq!(totp.get_otp_with_counter(8, 0), (7081804, 37037036));
+    assert_eq!(totp.get_otp_with_counter(8, 1), (14050471, 37037037));
+}
+
+#[test]
+fn test_totp_current_otp_and_expiry() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109));
+    assert_eq!(totp.current_otp_and_expiry(8), (7081804, std::time::Duration::from_secs(1)));
+
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111110));
+    assert_eq!(totp.current_otp_and_expiry(8), (14050471, std::time::Duration::from_secs(30)));
+}
+
+#[test]
+fn test_totp_otp_with_validity() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109));
+    let otp = totp.get_otp_with_validity(8, 0);
+    assert_eq!(otp, TemporalOtp { code: 7081804, valid_from: 1111111080, valid_until: 1111111109, formatted: String::from("07081804") });
+    assert!(otp.is_valid_at(1111111080));
+    assert!(otp.is_valid_at(1111111109));
+    assert!(!otp.is_valid_at(1111111110));
+    assert!(!otp.is_valid_now());
+
+    let next = totp.get_otp_with_validity(8, 1);
+    assert_eq!((next.code, next.valid_from, next.valid_until), (14050471, 1111111110, 1111111139));
+
+}
+
+#[test]
+fn test_totp_window_progress() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(15));
+    assert_eq!(totp.time_window_progress(), 0.5);
+    assert_eq!(totp.time_window_progress_at(60), 0.0);
+    assert_eq!(totp.time_window_progress_at(66), 0.2);
+    assert!(totp.time_window_progress_at(89) < 1.0);
+}
+
+#[test]
+fn test_algorithm_from_str() {
+    assert_eq!("SHA1".parse(), Ok(HOTPAlgorithm::HMACSHA1));
+    assert_eq!("sha256".parse(), Ok(HOTPAlgorithm::HMACSHA256));
+    assert_eq!("HMACSHA1".parse(), Ok(HOTPAlgorithm::HMACSHA1));
+    assert_eq!("hmac-sha512".parse(), Ok(HOTPAlgorithm::HMACSHA512));
+    assert_eq!("MD5".parse::<HOTPAlgorithm>(), Err(OtpError::UnknownAlgorithm));
+
+    for algorithm in [HOTPAlgorithm::HMACSHA1, HOTPAlgorithm::HMACSHA256, HOTPAlgorithm::HMACSHA512] {
+        assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+    }
+}
+
+#[test]
+fn test_algorithm_default() {
+    assert_eq!(HOTPAlgorithm::default(), HOTPAlgorithm::HMACSHA1);
+}
+
+#[cfg(feature = "base58")]
+#[test]
+fn test_hotp_base58() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(hotp.get_secret_base58(), "15V8FMkzy9QZKJAk2zsoA6t41T95FYYLnk");
+    assert_eq!(HOTP::from_base58("15V8FMkzy9QZKJAk2zsoA6t41T95FYYLnk", HOTPAlgorithm::HMACSHA1).unwrap(), hotp);
+
+    assert_eq!(HOTP::from_base58("15V8FMkzy9QZKJAk2zsoA6t41T95FYYLnm", HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidBase58));
+    assert_eq!(HOTP::from_base58("0OIl", HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidBase58));
+}
+
+#[cfg(feature = "shamir")]
+#[test]
+fn test_hotp_split_threshold() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let shares = hotp.split_threshold(5, 3).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert_eq!(shares.iter().map(|share| share.index()).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert!(shares.iter().all(|share| share.threshold() == 3));
+
+    let quorum = [shares[4].clone(), shares[0].clone(), shares[2].clone()];
+    assert_eq!(HOTP::recombine(&quorum, HOTPAlgorithm::HMACSHA1).unwrap(), hotp);
+    assert_eq!(HOTP::recombine(&shares, HOTPAlgorithm::HMACSHA1).unwrap(), hotp);
+    assert_eq!(HOTP::recombine(&shares[..2], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InsufficientShares));
+    assert_eq!(HOTP::recombine(&[shares[0].clone(), shares[0].clone(), shares[1].clone()], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InsufficientShares));
+    assert_eq!(HOTP::recombine(&[], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InsufficientShares));
+
+    let loaded: Vec<SecretShare> = shares.iter().map(|share| SecretShare::from_bytes(&share.to_bytes()).unwrap()).collect();
+    assert_eq!(loaded, shares);
+    assert_eq!(SecretShare::from_bytes(&[3, 1]).err(), Some(OtpError::InvalidShare));
+
+    let other_split = hotp.split_threshold(3, 2).unwrap();
+    assert_eq!(HOTP::recombine(&[shares[0].clone(), shares[1].clone(), other_split[2].clone()], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidShare));
+    let same_threshold = hotp.split_threshold(5, 3).unwrap();
+    assert_eq!(HOTP::recombine(&[shares[0].clone(), shares[1].clone(), same_threshold[2].clone()], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidShare));
+    let other_secret = HOTP::from_bin(b"09876543210987654321").unwrap().split_threshold(5, 3).unwrap();
+    assert_eq!(HOTP::recombine(&[shares[0].clone(), shares[1].clone(), other_secret[2].clone()], HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidShare));
+    assert_eq!(hotp.split_threshold(2, 3).err(), Some(OtpError::InvalidThreshold));
+    assert_eq!(hotp.split_threshold(2, 0).err(), Some(OtpError::InvalidThreshold));
+
+    let short = HOTP::from_bin_unchecked(b"too short").split_threshold(2, 2).unwrap();
+    assert_eq!(HOTP::recombine(&short, HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::SecretTooShort { actual: 9, minimum: 16 }));
+}
+
+#[test]
+fn test_hotp_hex() {
+    let hotp = HOTP::from_hex("3132333435363738393031323334353637383930", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.secret, b"12345678901234567890");
+    assert_eq!(hotp.get_secret_hex(), "3132333435363738393031323334353637383930");
+    assert_eq!(HOTP::from_hex("4A4b4A4b4A4b4A4b4A4b4A4b4A4b4A4b", HOTPAlgorithm::HMACSHA1).unwrap().secret, [0x4a, 0x4b].repeat(8));
+
+    assert_eq!(HOTP::from_hex("313", HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidHex));
+    assert_eq!(HOTP::from_hex("zz", HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidHex));
+}
+
+#[test]
+fn test_hotp_from_hex_or_base32() {
+    let expected = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(HOTP::from_hex_or_base32("3132333435363738393031323334353637383930", HOTPAlgorithm::HMACSHA1).unwrap(), expected);
+    assert_eq!(HOTP::from_hex_or_base32("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", HOTPAlgorithm::HMACSHA1).unwrap(), expected);
+
+    let ambiguous = "2345672345672345672345672345672345672345";
+    assert_eq!(HOTP::from_hex_or_base32(ambiguous, HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::AmbiguousEncoding));
+    assert_eq!(HOTP::from_hex_or_base32_with_hint(ambiguous, HOTPAlgorithm::HMACSHA1, SecretFormat::Hex).unwrap().secret.len(), 20);
+    assert_eq!(HOTP::from_hex_or_base32_with_hint(ambiguous, HOTPAlgorithm::HMACSHA1, SecretFormat::Base32).unwrap().secret.len(), 25);
+    assert_eq!(HOTP::from_hex_or_base32("not a secret", HOTPAlgorithm::HMACSHA1).err(), Some(OtpError::InvalidEncoding));
+}
+
+#[test]
+fn test_totp_fixed_clock() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let vectors = [
+        (59, 94287082),
+        (1111111109, 7081804),
+        (1111111111, 14050471),
+        (1234567890, 89005924),
+        (2000000000, 69279037),
+        (20000000000, 65353130),
+    ];
+
+    for (time, otp) in vectors {
+        let totp = TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 0, FixedClock(time));
+        assert_eq!(totp.get_otp(8, 0), otp);
+        assert!(totp.verify(otp, 8));
+    }
+
+    let totp = TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, FixedClock(65));
+    assert_eq!(totp.time_elapsed(), 5);
+    assert_eq!(totp.time_remaining(), 25);
+    assert_eq!(totp.verify_with_window(94287082, 8, 1), TotpVerificationResult::Accepted { window_offset: -1 });
+}
+
+#[test]
+fn test_new_with_length() {
+    let hotp = HOTP::new_with_length(HOTPAlgorithm::HMACSHA1, SecretLength::try_new(16).unwrap()).unwrap();
+    assert_eq!(hotp.secret.len(), 16);
+    assert_eq!(hotp.algorithm, HOTPAlgorithm::HMACSHA1);
+    assert_eq!(HOTP::new_with_length(HOTPAlgorithm::HMACSHA512, SecretLength::SHA512).unwrap().len(), 64);
+
+    assert_eq!(SecretLength::try_new(10), Err(OtpError::SecretTooShort { actual: 10, minimum: 16 }));
+    assert_eq!(SecretLength::try_new(1025), Err(OtpError::SecretTooLong { actual: 1025, maximum: 1024 }));
+    assert_eq!(SecretLength::try_new(1024).unwrap().get(), 1024);
+    assert_eq!([SecretLength::SHA1.get(), SecretLength::SHA256.get(), SecretLength::SHA512.get()], [20, 32, 64]);
+}
+
+#[test]
+fn test_totp_builder() {
+    let totp = TOTP::builder()
+        .secret(HOTP::from_bin(b"12345678901234567890").unwrap())
+        .time_step(60)
+        .start_time(10)
+        .issuer("Example Co")
+        .account_name("alice@example.com")
+        .build()
+        .unwrap();
+    assert_eq!(totp.time_step, 60);
+    assert_eq!(totp.start_time, 10);
+    assert_eq!(totp.issuer(), Some("Example Co"));
+    assert_eq!(totp.account_name(), Some("alice@example.com"));
+
+    assert_eq!(TOTPBuilder::new().build().err(), Some(OtpError::MissingSecret));
+    assert_eq!(TOTPBuilder::new()
+        .secret(HOTP::from_bin(b"12345678901234567890").unwrap())
+        .time_step(0)
+        .build()
+        .err(), Some(OtpError::InvalidTimeStep));
+}
+
+#[test]
+fn test_totp_get_otp_at() {
+    let sha1 = TOTP::new(HOTP::from_base32_predefined_algorithm("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", HOTPAlgorithm::HMACSHA1).unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    let sha256 = TOTP::new(HOTP::from_base32_predefined_algorithm("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZA====", HOTPAlgorithm::HMACSHA256).unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    let sha512 = TOTP::new(HOTP::from_base32_predefined_algorithm("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNA=", HOTPAlgorithm::HMACSHA512).unwrap(), TimeStep::THIRTY_SECONDS, 0);
+
+    // RFC6238 appendix B
+    let vectors = [
+        (59, 94287082, 46119246, 90693936),
+        (1111111109, 7081804, 68084774, 25091201),
+        (1111111111, 14050471, 67062674, 99943326),
+        (1234567890, 89005924, 91819424, 93441116),
+        (2000000000, 69279037, 90698825, 38618901),
+        (20000000000, 65353130, 77737706, 47863826),
+    ];
+    for (time, otp_sha1, otp_sha256, otp_sha512) in vectors {
+        assert_eq!(sha1.get_otp_at(time, 8), otp_sha1);
+        assert_eq!(sha256.get_otp_at(time, 8), otp_sha256);
+        assert_eq!(sha512.get_otp_at(time, 8), otp_sha512);
+    }
+
+    let shifted = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 1000);
+    assert_eq!(shifted.get_otp_at(1059, 8), 94287082);
+}
+
+#[test]
+fn test_debug_redacts_secret() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(format!("{:?}", hotp), "HOTP { algorithm: HMACSHA1, secret: [REDACTED 20 bytes] }");
+
+    let totp = TOTP::new(hotp, TimeStep::THIRTY_SECONDS, 0);
+    assert_eq!(format!("{:?}", totp), "TOTP { secret: HOTP { algorithm: HMACSHA1, secret: [REDACTED 20 bytes] }, \
+        time_step: 30, start_time: 0, clock: SystemTimeClock, issuer: None, account_name: None }");
+}
+
+#[test]
+fn test_hotp_verify_otp() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+
+    // RFC4226 appendix D
+    assert!(hotp.verify_otp(755224, 0, 6));
+    assert!(hotp.verify_otp(338314, 4, 6));
+    assert!(!hotp.verify_otp(338314, 5, 6));
+
+    assert_eq!(hotp.verify_otp_with_lookahead(338314, 2, 3, 6), Some(4));
+    assert_eq!(hotp.verify_otp_with_lookahead(338314, 2, 1, 6), None);
+    assert_eq!(hotp.verify_otp_with_lookahead(338314, u64::MAX, 5, 6), None);
+}
+
+#[test]
+fn test_totp_clone() {
+    let totp = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0);
+    let mut copy = totp.clone();
+    copy.secret.secret[0] = 0;
+
+    assert_eq!(totp.secret.secret, b"12345678901234567890");
+    assert_eq!(totp.get_otp_at(59, 8), 94287082);
+    assert_ne!(copy.get_otp_at(59, 8), 94287082);
+}
+
+#[test]
+fn test_hotp_eq() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(hotp, HOTP::from_hex("3132333435363738393031323334353637383930", HOTPAlgorithm::HMACSHA1).unwrap());
+    assert_ne!(hotp, HOTP::from_hex("3132333435363738393031323334353637383930", HOTPAlgorithm::HMACSHA256).unwrap());
+    assert_ne!(hotp, HOTP::from_bin(b"12345678901234567891").unwrap());
+}
+
+#[test]
+fn test_hotp_accessors() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(hotp.as_slice(), b"12345678901234567890");
+    assert_eq!(hotp.len(), 20);
+    assert!(!hotp.is_empty());
+    assert!(HOTP::from_bin_unchecked(&[]).is_empty());
+}
+
+#[test]
+fn test_hotp_base64() {
+    let hotp = HOTP::from_base64("MTIzNDU2Nzg5MDEyMzQ1Njc4OTA=", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.as_slice(), b"12345678901234567890");
+    assert_eq!(hotp.get_secret_base64(), "MTIzNDU2Nzg5MDEyMzQ1Njc4OTA");
+    assert_eq!(HOTP::from_base64("MTIzNDU2Nzg5MDEyMzQ1Njc4OTA", HOTPAlgorithm::HMACSHA1).unwrap(), hotp);
+
+    let url_safe = HOTP::from_base64("-_-_-_-_-_-_-_-_-_-_-_8", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(url_safe.as_slice()[..3], [0xfb, 0xff, 0xbf]);
+    assert_eq!(HOTP::from_base64("+/+/+/+/+/+/+/+/+/+/+/8=", HOTPAlgorithm::HMACSHA1).unwrap(), url_safe);
+    assert_eq!(url_safe.get_secret_base64(), "+/+/+/+/+/+/+/+/+/+/+/8");
+
+    assert_eq!(HOTP::from_base64("", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::EmptySecret);
+    assert_eq!(HOTP::from_base64("MTIz*DU2", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::InvalidBase64);
+    assert_eq!(HOTP::from_base64("MTIzN", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::InvalidBase64Padding);
+    assert_eq!(HOTP::from_base64("-_8==", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::InvalidBase64Padding);
+}
+
+#[test]
+fn test_hotp_minimum_secret_length() {
+    let too_short = OtpError::SecretTooShort { actual: 10, minimum: 16 };
+    assert_eq!(HOTP::from_bin(b"1234567890").unwrap_err(), too_short);
+    assert_eq!(HOTP::from_base32("GEZDGNBVGY3TQOJQ").unwrap_err(), too_short);
+    assert_eq!(HOTP::from_base32_predefined_algorithm("GEZDGNBVGY3TQOJQ", HOTPAlgorithm::HMACSHA1).unwrap_err(), too_short);
+    assert_eq!(HOTP::from_hex("31323334353637383930", HOTPAlgorithm::HMACSHA1).unwrap_err(), too_short);
+    assert_eq!(HOTP::from_base64("MTIzNDU2Nzg5MA", HOTPAlgorithm::HMACSHA1).unwrap_err(), too_short);
+
+    let short = HOTP::from_base32_unchecked("GEZDGNBVGY3TQOJQ");
+    assert_eq!(short, HOTP::from_bin_unchecked(b"1234567890"));
+    assert_eq!(short.validate_length(), Err(too_short));
+    assert_eq!(HOTP::from_bin(b"1234567890123456").unwrap().validate_length(), Ok(()));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_ring_algorithm() {
+    assert_eq!(HOTPAlgorithm::HMACSHA256.ring_algorithm(), Some(ring::hmac::HMAC_SHA256));
+    assert_eq!(HOTPAlgorithm::HMACSHA3_256.ring_algorithm(), None);
+    assert_eq!(HOTPAlgorithm::HMACSHA512.get_algorithm(), ring::hmac::HMAC_SHA512);
+}
+
+#[test]
+fn test_sha3() {
+    let sha3_256 = HOTP {
+        secret: Vec::from(&b"12345678901234567890123456789012"[..]),
+        algorithm: HOTPAlgorithm::HMACSHA3_256,
+    };
+    let sha3_512 = HOTP {
+        secret: Vec::from(&b"1234567890123456789012345678901234567890123456789012345678901234"[..]),
+        algorithm: HOTPAlgorithm::HMACSHA3_512,
+    };
+    let vectors = [
+        (0, 83355535, 29766274),
+        (1, 3503818, 1892432),
+        (0x023523ec, 384900, 25574199),
+        (0x27bc86aa, 23378950, 29527821),
+    ];
+
+    for (counter, otp_sha3_256, otp_sha3_512) in vectors {
+        assert_eq!(sha3_256.get_otp(&utils::num_to_buffer(counter), Digits::EIGHT), otp_sha3_256);
+        assert_eq!(sha3_512.get_otp(&utils::num_to_buffer(counter), Digits::EIGHT), otp_sha3_512);
+    }
+
+    assert_eq!(HOTP::new(HOTPAlgorithm::HMACSHA3_256).unwrap().len(), 32);
+    assert_eq!(HOTP::new(HOTPAlgorithm::HMACSHA3_512).unwrap().len(), 64);
+    assert_eq!("sha3-256".parse(), Ok(HOTPAlgorithm::HMACSHA3_256));
+    assert_eq!(HOTPAlgorithm::HMACSHA3_512.to_string(), "SHA3-512");
+}
+
+#[test]
+fn test_totp_counter() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109));
+    assert_eq!(totp.current_counter(), 0x023523ec);
+    assert_eq!(totp.counter_at(59), 1);
+    assert_eq!(totp.counter_at(60), 2);
+
+    let shifted = TOTP::new(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 100);
+    assert_eq!(shifted.counter_at(129), 0);
+    assert_eq!(shifted.counter_at(130), 1);
+    assert_eq!(shifted.counter_at(50), 0);
+}
+
+#[test]
+fn test_totp_get_otp_range() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109));
+    assert_eq!(totp.get_otp_range(8, -1, 1), vec![
+        (-1, totp.get_otp_at(1111111079, 8)),
+        (0, 7081804),
+        (1, 14050471),
+    ]);
+    assert!(totp.get_otp_range(8, 1, -1).is_empty());
+}
+
+#[test]
+fn test_hotp_get_otp_counter() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+
+    // RFC4226 appendix D
+    let vectors = [755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489];
+    for (counter, otp) in vectors.iter().enumerate() {
+        assert_eq!(hotp.get_otp_counter(counter as u64, 6), *otp);
+    }
+}
+
+#[test]
+fn test_algorithm_output_len() {
+    assert_eq!(HOTPAlgorithm::HMACSHA1.output_len(), 20);
+    assert_eq!(HOTPAlgorithm::HMACSHA256.output_len(), 32);
+    assert_eq!(HOTPAlgorithm::HMACSHA512.output_len(), 64);
+    assert_eq!(HOTPAlgorithm::HMACSHA3_256.output_len(), 32);
+    assert_eq!(HOTPAlgorithm::HMACSHA3_512.output_len(), 64);
+
+    assert_eq!(HOTPAlgorithm::HMACSHA1.block_size(), 64);
+    assert_eq!(HOTPAlgorithm::HMACSHA256.block_size(), 64);
+    assert_eq!(HOTPAlgorithm::HMACSHA512.block_size(), 128);
+    assert_eq!(HOTPAlgorithm::HMACSHA3_256.block_size(), <sha3::Sha3_256 as hmac::digest::core_api::BlockSizeUser>::block_size());
+    assert_eq!(HOTPAlgorithm::HMACSHA3_512.block_size(), <sha3::Sha3_512 as hmac::digest::core_api::BlockSizeUser>::block_size());
+}
+
+#[test]
+fn test_hotp_try_get_otp() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let counter = utils::num_to_buffer(0);
+
+    assert_eq!(hotp.try_get_otp(&counter, 6), Ok(755224));
+    assert_eq!(hotp.try_get_otp(&counter, 10), Ok(1284755224));
+    assert_eq!(hotp.try_get_otp(&counter, 0), Err(OtpError::InvalidDigits));
+    assert_eq!(hotp.try_get_otp(&counter, 11), Err(OtpError::InvalidDigits));
+}
+
+#[test]
+#[should_panic]
+#[allow(deprecated)]
+fn test_hotp_get_otp_invalid_digits() {
+    HOTP::from_bin(b"12345678901234567890").unwrap().get_otp_u32(&utils::num_to_buffer(0), 11);
+}
+
+#[test]
+fn test_digits() {
+    assert_eq!(Digits::try_new(6), Ok(Digits::SIX));
+    assert_eq!([Digits::SEVEN.get(), Digits::EIGHT.get()], [7, 8]);
+    assert_eq!(Digits::try_new(0), Err(OtpError::InvalidDigits));
+    assert_eq!(Digits::try_new(11), Err(OtpError::InvalidDigits));
+}
+
+#[test]
+fn test_invalid_digits_are_rejected() {
+    let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    assert_eq!(hotp(0, secret, 0), None);
+    assert_eq!(totp(secret, 11, 30, 0), None);
+    assert_eq!(validate_hotp(755224, 1, 0, secret, 11), None);
+    assert_eq!(validate_totp(755224, 1, secret, 0, 30, 0), None);
+
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert!(!hotp.validate(&utils::num_to_buffer(0), 11, 755224));
+    assert!(!hotp.verify_otp(755224, 0, 0));
+    assert_eq!(hotp.verify_otp_with_lookahead(755224, 0, 2, 11), None);
+
+    let totp = TOTP::new_with_clock(hotp, TimeStep::THIRTY_SECONDS, 0, FixedClock(59));
+    assert_eq!(totp.verify_with_window(94287082, 11, 1), TotpVerificationResult::Rejected);
+    assert!(!totp.verify(94287082, 0));
+}
+
+#[test]
+fn test_totp_try_get_otp() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+
+    let totp = TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 0, FixedClock(59));
+    assert_eq!(totp.try_get_otp(8, 0), Ok(94287082));
+    assert_eq!(totp.try_get_otp(11, 0), Err(OtpError::InvalidDigits));
+    assert_eq!(totp.try_get_otp(8, -1), Ok(84755224));
+    assert_eq!(totp.try_get_otp(8, -2), Err(OtpError::InvalidOffset));
+
+    let before_start = TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 100, FixedClock(0));
+    assert_eq!(before_start.try_get_otp(8, 0), Err(OtpError::ClockError));
+
+    struct BrokenClock;
+
+    impl TimeProvider for BrokenClock {
+        fn unix_seconds(&self) -> Result<u64, OtpError> {
+            Err(OtpError::ClockError)
+        }
+    }
+
+    let broken = TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, BrokenClock);
+    assert_eq!(broken.try_get_otp(8, 0), Err(OtpError::ClockError));
+}
+
+#[test]
+fn test_steam_guard() {
+    let secret = HOTP::from_base32_predefined_algorithm("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", HOTPAlgorithm::HMACSHA256).unwrap();
+    let steam = SteamGuardTotp::new_with_clock(secret, FixedClock(59));
+    assert_eq!(steam.get_steam_otp(0), "PV9M4");
+    assert_eq!(steam.get_steam_otp_at(1111111109), "PY4YB");
+    assert_eq!(steam.get_steam_otp_at(1234567890), "VHHQY");
+    assert_eq!(SteamGuardTotp::new(HOTP::from_bin(b"12345678901234567890").unwrap()).get_steam_otp(0).len(), 5);
+}
+
+#[test]
+fn test_recovery_codes() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let codes = secret.generate_recovery_codes(10, 12).unwrap();
+    assert_eq!(codes.len(), 10);
+
+    for code in &codes {
+        assert_eq!(code.code.len(), 14);
+        assert_eq!(code.code.matches('-').count(), 2);
+        assert!(secret.verify_recovery_code(&code.code, &code.hash));
+        assert!(secret.verify_recovery_code(&code.code.to_lowercase().replace('-', ""), &code.hash));
+    }
+    assert!(!secret.verify_recovery_code(&codes[0].code, &codes[1].hash));
+    assert!(!secret.verify_recovery_code("", &codes[0].hash));
+    assert!(!secret.verify_recovery_code(&codes[0].code.replace('-', "*"), &codes[0].hash));
+
+    let other = HOTP::from_bin(b"09876543210987654321").unwrap();
+    assert!(!other.verify_recovery_code(&codes[0].code, &codes[0].hash));
+}
+
+#[test]
+fn test_hotp_from_passphrase() {
+    let hotp = HOTP::new_from_passphrase(b"correct horse battery staple", b"example salt", 2, HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.get_secret_hex(), "c8c05bb7e82faf613a8b977843de3c73faf52dfc");
+
+    let sha512 = HOTP::new_from_passphrase(b"correct horse battery staple", b"example salt", 2, HOTPAlgorithm::HMACSHA512).unwrap();
+    assert_eq!(sha512.len(), 64);
+    assert_eq!(HOTP::new_from_passphrase(b"passphrase", b"salt", 0, HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::InvalidIterations);
+}
+
+#[test]
+fn test_hotp_from_password() {
+    let forced = HOTP::new_from_password(b"correct horse battery staple", b"example salt", 2, HOTPAlgorithm::HMACSHA1, true).unwrap();
+    assert_eq!(forced.get_secret_hex(), "c8c05bb7e82faf613a8b977843de3c73faf52dfc");
+    assert_eq!(HOTP::new_from_password(b"password", b"salt", 2, HOTPAlgorithm::HMACSHA1, false).unwrap_err(), OtpError::WeakIterationCount);
+    assert_eq!(HOTP::new_from_password(b"password", b"salt", 0, HOTPAlgorithm::HMACSHA1, true).unwrap_err(), OtpError::InvalidIterations);
+
+    let minimum = HOTP::new_from_password(b"password", b"salt", PBKDF2_MINIMUM_ITERATIONS, HOTPAlgorithm::HMACSHA256, false).unwrap();
+    assert_eq!(minimum.len(), 32);
+}
+
+#[test]
+fn test_totp_default_constructors() {
+    let totp = TOTP::new_default(HOTP::from_bin(b"12345678901234567890").unwrap());
+    assert_eq!(totp.time_step, DEFAULT_TIME_STEP);
+    assert_eq!(totp.start_time, 0);
+
+    let sha256 = TOTP::new_sha256_30s(b"12345678901234567890123456789012").unwrap();
+    assert_eq!(sha256.secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(sha256.get_otp_at(59, 8), 46119246);
+    assert_eq!(TOTP::new_sha1_30s(b"12345678901234567890").unwrap().get_otp_at(59, 8), 94287082);
+    assert_eq!(TOTP::new_sha512_30s(b"1234567890").unwrap_err(), OtpError::SecretTooShort { actual: 10, minimum: 16 });
+}
+
+#[test]
+fn test_totp_prev_next_otp() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(TOTP::new_with_clock(secret.clone(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111)).get_prev_otp(8), 7081804);
+    assert_eq!(TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111109)).get_next_otp(8), 14050471);
+}
+
+#[test]
+fn test_hotp_base32_crockford() {
+    let hotp = HOTP::from_base32_crockford("64s36d1n6rvkge9g64s36d1n6rvkge9g", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.as_slice(), b"12345678901234567890");
+    assert_eq!(hotp.get_secret_base32_crockford(), "64S36D1N6RVKGE9G64S36D1N6RVKGE9G");
+    assert_eq!(HOTP::from_base32_crockford("64S36D1N6RVKGE9G64S36D1N6RVKGE9GA", HOTPAlgorithm::HMACSHA1).unwrap(), hotp);
+
+    assert_eq!(HOTP::from_base32_crockford("64S36D1N6RVKGE9G64S36D1N6RVKGE9GB", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::InvalidBase32);
+    assert_eq!(HOTP::from_base32_crockford("ZW0G", HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::SecretTooShort { actual: 2, minimum: 16 });
+}
+
+#[test]
+fn test_hotp_iterator() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let otps: Vec<u32> = HOTPIterator::new(secret.clone(), 0, 6).take(10).collect();
+    assert_eq!(otps, [755224, 287082, 359152, 969429, 338314, 254676, 287922, 162583, 399871, 520489]);
+
+    assert_eq!(HOTPIterator::new(secret.clone(), 8, 6).next(), Some(399871));
+    assert_eq!(HOTPIterator::new(secret, u64::MAX, 6).count(), 1);
+}
+
+#[test]
+fn test_totp_all_valid_otps() {
+    let secret = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let totp = TOTP::new_with_clock(secret, TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111110));
+    assert_eq!(totp.get_all_valid_otps(8, 1), [7081804, 14050471, totp.get_otp(8, 1)]);
+    assert_eq!(totp.get_all_valid_otps(8, 0), [14050471]);
+    assert_eq!(totp.get_all_valid_otps(1, 15).len(), 10);
+}
+
+#[test]
+fn test_otp_eq() {
+    assert!(otp_eq(12345, 12345));
+    assert!(!otp_eq(12345, 12346));
+    assert!(!otp_eq(0, u32::MAX));
+}
+
+#[cfg(feature = "argon2")]
+#[test]
+fn test_hotp_from_argon2() {
+    let params = Argon2Params { m_cost: 64, t_cost: 1, p_cost: 1 };
+    let hotp = HOTP::new_from_argon2(b"device-serial-1234", b"example salt", params, HOTPAlgorithm::HMACSHA256).unwrap();
+    assert_eq!(hotp.len(), 32);
+    assert_eq!(HOTP::new_from_argon2(b"device-serial-1234", b"example salt", params, HOTPAlgorithm::HMACSHA256).unwrap(), hotp);
+    assert_ne!(HOTP::new_from_argon2(b"device-serial-1234", b"other salt", params, HOTPAlgorithm::HMACSHA256).unwrap(), hotp);
+
+    assert_eq!(HOTP::new_from_argon2(b"password", b"short", params, HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::KeyDerivation);
+    assert_eq!(HOTP::new_from_argon2(b"password", b"example salt", Argon2Params { t_cost: 0, ..params }, HOTPAlgorithm::HMACSHA1).unwrap_err(), OtpError::KeyDerivation);
+    assert_eq!(Argon2Params::default(), Argon2Params { m_cost: 19456, t_cost: 2, p_cost: 1 });
+}
+
+#[test]
+fn test_hotp_short_secret_fixups() {
+    let short = HOTP::from_bin_unchecked(b"1234567890");
+
+    let mut padded = short.clone();
+    padded.pad_to_minimum_length(RFC_MINIMUM_SECRET_BYTES);
+    assert_eq!(padded.as_slice(), b"1234567890\0\0\0\0\0\0");
+    assert_eq!(padded.validate_length(), Ok(()));
+    assert_eq!(padded.get_otp_counter(0, 6), short.get_otp_counter(0, 6));
+
+    let mut expanded = short.clone();
+    expanded.expand_with_hkdf(20, b"otp").unwrap();
+    assert_eq!(expanded.get_secret_hex(), "159c7637e24aba5aa95b4c683323868ba7d5df74");
+    assert_eq!(expanded.clone().expand_with_hkdf(255 * 32 + 1, b"otp"), Err(OtpError::KeyDerivation));
+}
+
+#[test]
+fn test_hotp_from_ascii() {
+    let hotp = HOTP::from_ascii("12345678901234567890", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp, HOTP::from_bin(b"12345678901234567890").unwrap());
+    assert_eq!(hotp.get_otp_counter(0, 6), 755224);
+    assert_eq!(HOTP::from_ascii("MySecretKey12345", HOTPAlgorithm::HMACSHA256).unwrap().as_slice(), b"MySecretKey12345");
+    assert_eq!(HOTP::from_ascii("Schlüssel1234567", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidAscii));
+    assert_eq!(HOTP::from_ascii("short", HOTPAlgorithm::HMACSHA1), Err(OtpError::SecretTooShort { actual: 5, minimum: 16 }));
+}
+
+#[test]
+fn test_hotp_from_env() {
+    std::env::set_var("LIBOTP_TEST_HEX_SECRET", "3132333435363738393031323334353637383930");
+    std::env::set_var("LIBOTP_TEST_BASE32_SECRET", " gezdgnbvgy3tqojqgezdgnbvgy3tqojq\n");
+    std::env::set_var("LIBOTP_TEST_BASE64_SECRET", "MTIzNDU2Nzg5MDEyMzQ1Njc4OTA=");
+    std::env::set_var("LIBOTP_TEST_INVALID_SECRET", "not a secret!");
+    std::env::set_var("LIBOTP_TEST_AMBIGUOUS_SECRET", "2345672345672345672345672345672345672345");
+
+    let expected = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(HOTP::from_env("LIBOTP_TEST_HEX_SECRET", HOTPAlgorithm::HMACSHA1), Ok(expected.clone()));
+    assert_eq!(HOTP::from_env("LIBOTP_TEST_BASE32_SECRET", HOTPAlgorithm::HMACSHA1), Ok(expected.clone()));
+    assert_eq!(HOTP::from_env_with_format("LIBOTP_TEST_BASE64_SECRET", HOTPAlgorithm::HMACSHA1, SecretFormat::Base64), Ok(expected.clone()));
+    assert_eq!(HOTP::from_env_with_format("LIBOTP_TEST_INVALID_SECRET", HOTPAlgorithm::HMACSHA1, SecretFormat::Base64), Err(OtpError::InvalidEncoding));
+    assert_eq!(HOTP::from_env("LIBOTP_TEST_INVALID_SECRET", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidEncoding));
+    assert_eq!(HOTP::from_env("LIBOTP_TEST_MISSING_SECRET", HOTPAlgorithm::HMACSHA1), Err(OtpError::EnvVarMissing));
+
+    // same detection rules as from_hex_or_base32
+    assert_eq!(HOTP::from_env("LIBOTP_TEST_AMBIGUOUS_SECRET", HOTPAlgorithm::HMACSHA1), Err(OtpError::AmbiguousEncoding));
+    assert_eq!(HOTP::from_env_with_format("LIBOTP_TEST_AMBIGUOUS_SECRET", HOTPAlgorithm::HMACSHA1, SecretFormat::Hex).unwrap().secret.len(), 20);
+    assert_eq!(HOTP::from_env_with_format("LIBOTP_TEST_AMBIGUOUS_SECRET", HOTPAlgorithm::HMACSHA1, SecretFormat::Base32).unwrap().secret.len(), 25);
+}
+
+#[test]
+fn test_hotp_entropy_estimate() {
+    assert_eq!(HOTP::from_bin_unchecked(&[0u8; 20]).entropy_estimate(), 0.0);
+    assert_eq!(HOTP::from_bin_unchecked(&[42]).entropy_estimate(), 0.0);
+    assert_eq!(HOTP::from_bin(&(0..32).collect::<Vec<u8>>()).unwrap().entropy_estimate(), 256.0);
+    let text = HOTP::from_bin(b"12345678901234567890").unwrap().entropy_estimate();
+    assert!((text - 160.0 * 10f64.log2() / 20f64.log2()).abs() < 1e-9);
+
+    assert!(!HOTP::from_bin(b"12345678901234567890").unwrap().is_high_entropy());
+    assert!(!HOTP::from_bin_unchecked(&[7u8; 32]).is_high_entropy());
+    assert!(!HOTP::from_bin_unchecked(b"abababababababababab").is_high_entropy());
+    assert!(!HOTP::from_bin_unchecked(b"0123456789").is_high_entropy());
+    assert!(HOTP::from_bin(&(0..64).collect::<Vec<u8>>()).unwrap().is_high_entropy());
+    assert!(HOTP::from_bin(&(0..32).collect::<Vec<u8>>()).unwrap().is_high_entropy());
+    assert!(HOTP::from_base32("VMNW2EC7X3OCJHITBVSVZW5MVCUIL5SR").unwrap().is_high_entropy());
+    assert!(HOTP::new(HOTPAlgorithm::HMACSHA1).unwrap().is_high_entropy());
+    assert!(HOTP::new(HOTPAlgorithm::HMACSHA256).unwrap().is_high_entropy());
+}
+
+#[test]
+fn test_hotp_new_from_hkdf() {
+    // RFC5869 test case 1, truncated to the SHA256 output length
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let hotp = HOTP::new_from_hkdf(&[0x0b; 22], Some(&salt), &info, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(hotp.get_secret_hex(), "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf");
+
+    // RFC5869 test case 7, SHA1 without salt
+    let hotp = HOTP::new_from_hkdf(&[0x0c; 22], None, &[], HOTPAlgorithm::HMACSHA1);
+    assert_eq!(hotp.get_secret_hex(), "2c91117204d745f3500d636a62f64f0ab3bae548");
+
+    for algorithm in [HOTPAlgorithm::HMACSHA512, HOTPAlgorithm::HMACSHA3_256, HOTPAlgorithm::HMACSHA3_512] {
+        let hotp = HOTP::new_from_hkdf(b"master secret", None, b"account 1", algorithm);
+        assert_eq!(hotp.len(), algorithm.output_len());
+        assert_ne!(hotp, HOTP::new_from_hkdf(b"master secret", None, b"account 2", algorithm));
+    }
+}
+
+#[test]
+fn test_hotp_new_from_uuid() {
+    let hotp = HOTP::new_from_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8", HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.len(), 20);
+    assert_eq!(hotp, HOTP::new_from_uuid("67E5504410B1426F9247BB680E5FE0C8", HOTPAlgorithm::HMACSHA1).unwrap());
+
+    let mut expected = HOTP::from_hex("67e5504410b1426f9247bb680e5fe0c8", HOTPAlgorithm::HMACSHA1).unwrap();
+    expected.expand_with_hkdf(20, b"otp-secret-derivation").unwrap();
+    assert_eq!(hotp, expected);
+    assert_eq!(HOTP::new_from_uuid("67e5504410b1426f9247bb680e5fe0c8", HOTPAlgorithm::HMACSHA512).unwrap().len(), 64);
+
+    assert_eq!(HOTP::new_from_uuid("67e55044-10b1-426f-9247-bb680e5fe0c", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidUuid));
+    assert_eq!(HOTP::new_from_uuid("67e55044-10b1-426f-9247_bb680e5fe0c8", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidUuid));
+    assert_eq!(HOTP::new_from_uuid("67e55044-10b1-426f-9247-bb680e5fe0cg", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidUuid));
+    assert_eq!(HOTP::new_from_uuid("67e55044--0b1-426f-9247-bb680e5fe0c8", HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidUuid));
+}
+
+#[test]
+fn test_hotp_new_from_dice_throws() {
+    let throws: Vec<u8> = (0..50).map(|i| (i % 6) + 1).collect();
+    let hotp = HOTP::new_from_dice_throws(&throws, 6, HOTPAlgorithm::HMACSHA1).unwrap();
+    assert_eq!(hotp.len(), 20);
+    assert_eq!(hotp, HOTP::new_from_hkdf(&throws, None, b"otp-dice-secret-derivation", HOTPAlgorithm::HMACSHA1));
+    assert_eq!(HOTP::new_from_dice_throws(&throws, 6, HOTPAlgorithm::HMACSHA512).unwrap().len(), 64);
+    assert_eq!(HOTP::new_from_dice_throws(&[1; 128], 2, HOTPAlgorithm::HMACSHA1).unwrap().len(), 20);
+
+    assert_eq!(HOTP::new_from_dice_throws(&throws[..49], 6, HOTPAlgorithm::HMACSHA1), Err(OtpError::InsufficientEntropy));
+    assert_eq!(HOTP::new_from_dice_throws(&[1; 127], 2, HOTPAlgorithm::HMACSHA1), Err(OtpError::InsufficientEntropy));
+    assert_eq!(HOTP::new_from_dice_throws(&[7; 50], 6, HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidDiceThrow));
+    assert_eq!(HOTP::new_from_dice_throws(&[0; 50], 6, HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidDiceThrow));
+    assert_eq!(HOTP::new_from_dice_throws(&[1; 200], 1, HOTPAlgorithm::HMACSHA1), Err(OtpError::InvalidDiceThrow));
+}
+
+#[test]
+fn test_hotp_fingerprint() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    assert_eq!(hotp.to_fingerprint(), "RUKO-CATU-JE");
+    assert_eq!(hotp.clone_with_new_algorithm(HOTPAlgorithm::HMACSHA512).to_fingerprint(), "RUKO-CATU-JE");
+    assert_eq!(HOTP::from_bin(b"12345678901234567891").unwrap().to_fingerprint(), "BAWO-WOHA-BU");
+}
+
+#[test]
+fn test_hotp_new_insecure_zeroed() {
+    let hotp = HOTP::new_insecure_zeroed(HOTPAlgorithm::HMACSHA256, 32);
+    assert_eq!(hotp.as_slice(), &[0u8; 32]);
+    assert_eq!(hotp.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(hotp.get_otp_counter(0, 6), HOTP::from_bin(&[0u8; 32]).unwrap().get_otp_counter(0, 6));
+}
+
+#[test]
+fn test_hotp_rotate() {
+    let mut hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let old = hotp.rotate(Vec::from(&b"09876543210987654321"[..])).unwrap();
+    assert_eq!(old, HOTP::from_bin(b"12345678901234567890").unwrap());
+    assert_eq!(hotp.as_slice(), b"09876543210987654321");
+    assert_eq!(hotp.algorithm, HOTPAlgorithm::HMACSHA1);
+    assert_eq!(old.get_otp_counter(0, 6), 755224);
+    assert_ne!(hotp.get_otp_counter(0, 6), 755224);
+
+    assert_eq!(hotp.rotate(Vec::from(&b"too short"[..])).err(), Some(OtpError::SecretTooShort { actual: 9, minimum: 16 }));
+    assert_eq!(hotp.as_slice(), b"09876543210987654321");
+}
+
+#[test]
+fn test_hotp_clone_with_new_algorithm() {
+    let sha1 = HOTP::from_bin(b"12345678901234567890").unwrap();
+    let sha256 = sha1.clone_with_new_algorithm(HOTPAlgorithm::HMACSHA256);
+    assert_eq!(sha256.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(sha256.as_slice(), sha1.as_slice());
+    assert_eq!(sha1.algorithm, HOTPAlgorithm::HMACSHA1);
+    assert_ne!(sha256.get_otp_counter(0, 6), sha1.get_otp_counter(0, 6));
+}
+
+#[test]
+fn test_rfc4226_test_vectors() {
+    let hotp = HOTP::rfc4226_test_secret();
+
+    // RFC4226 appendix D, table 1 and table 2 (the truncated values before the modulo)
+    let vectors = [
+        (1284755224, 755224), (1094287082, 287082), (137359152, 359152), (1726969429, 969429),
+        (1640338314, 338314), (868254676, 254676), (1918287922, 287922), (82162583, 162583),
+        (673399871, 399871), (645520489, 520489),
+    ];
+    for (counter, (truncated, otp)) in vectors.iter().enumerate() {
+        assert_eq!(hotp.get_otp_counter(counter as u64, 10), *truncated);
+        assert_eq!(hotp.get_otp_counter(counter as u64, 6), *otp);
+    }
+}
+
+#[test]
+fn test_rfc6238_test_vectors() {
+    let sha1 = TOTP::new(HOTP::rfc6238_sha1_secret(), TimeStep::THIRTY_SECONDS, 0);
+    let sha256 = TOTP::new(HOTP::rfc6238_sha256_secret(), TimeStep::THIRTY_SECONDS, 0);
+    let sha512 = TOTP::new(HOTP::rfc6238_sha512_secret(), TimeStep::THIRTY_SECONDS, 0);
+    assert_eq!(sha256.secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(sha512.secret.algorithm, HOTPAlgorithm::HMACSHA512);
+
+    // RFC6238 appendix B
+    let vectors = [
+        (59, 94287082, 46119246, 90693936),
+        (1111111109, 7081804, 68084774, 25091201),
+        (1111111111, 14050471, 67062674, 99943326),
+        (1234567890, 89005924, 91819424, 93441116),
+        (2000000000, 69279037, 90698825, 38618901),
+        (20000000000, 65353130, 77737706, 47863826),
+    ];
+    for (time, otp_sha1, otp_sha256, otp_sha512) in vectors {
+        assert_eq!(sha1.get_otp_at(time, 8), otp_sha1);
+        assert_eq!(sha256.get_otp_at(time, 8), otp_sha256);
+        assert_eq!(sha512.get_otp_at(time, 8), otp_sha512);
+    }
+}
+
+#[test]
+fn test_hotp_hmac_raw() {
+    let hotp = HOTP::from_bin(b"12345678901234567890").unwrap();
+
+    // RFC4226 appendix D, HMAC-SHA-1 value for counter 0
+    let hmac = hotp.hmac_raw(&utils::num_to_buffer(0));
+    assert_eq!(HOTP::from_bin_unchecked(&hmac).get_secret_hex(), "cc93cf18508d94934c64b65d8ba7667fb7cde4b0");
+    assert_eq!(HOTP::rfc6238_sha512_secret().hmac_raw(b"challenge").len(), 64);
+}
+
+#[test]
+fn test_hotp_sign_data() {
+    // RFC4231 test case 2
+    let key = HOTP { secret: Vec::from(&b"Jefe"[..]), algorithm: HOTPAlgorithm::HMACSHA256 };
+    let signature = key.sign_data(b"what do ya want for nothing?");
+    assert_eq!(HOTP::from_bin_unchecked(&signature).get_secret_hex(), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    assert_eq!(signature, key.hmac_raw(b"what do ya want for nothing?"));
+}
+
+#[test]
+fn test_drift_tolerant_totp() {
+    let totp = TOTP::new_with_clock(HOTP::from_bin(b"12345678901234567890").unwrap(), TimeStep::THIRTY_SECONDS, 0, FixedClock(1111111111));
+    let mut tolerant = DriftTolerantTOTP::new(totp, 1);
+    assert_eq!(tolerant.tolerance(), 1);
+    assert_eq!(tolerant.verify(7081804, 8), OtpValidationResult::Valid { window_offset: -1, counter_value: 37037036 });
+    assert_eq!(tolerant.verify(14050471, 8), OtpValidationResult::Valid { window_offset: 0, counter_value: 37037037 });
+    assert_eq!(tolerant.verify(14050471, 8), OtpValidationResult::ReplayDetected);
+    assert_eq!(tolerant.verify(7081804, 8), OtpValidationResult::ReplayDetected);
+    assert_eq!(tolerant.verify(12345678, 8), OtpValidationResult::InvalidCode);
+    assert_eq!(tolerant.verify(14050471, 11), OtpValidationResult::SecretError(OtpError::InvalidDigits));
+
+    tolerant.set_tolerance(0);
+    assert!(!tolerant.verify(7081804, 8).is_valid());
+    assert_eq!(tolerant.totp().time_step, 30);
+}
+
+#[test]
+fn test_time_step() {
+    assert_eq!(TimeStep::new(30), Ok(TimeStep::THIRTY_SECONDS));
+    assert_eq!(TimeStep::SIXTY_SECONDS.get(), 60);
+    assert_eq!(TimeStep::new(0), Err(OtpError::InvalidTimeStep));
+    assert_eq!(totp("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ", 6, 0, 0), None);
+}
+
+#[test]
+fn test_hotp_new_with_rng() {
+    struct CountingRng(u8);
+
+    impl rand_core::RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            for byte in dst {
+                *byte = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+    }
+
+    let hotp = HOTP::new_with_rng(HOTPAlgorithm::HMACSHA256, &mut CountingRng(0));
+    assert_eq!(hotp.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(hotp.as_slice(), (0..32).collect::<Vec<u8>>());
+}
+
+#[test]
+fn test_google_migration_payload() {
+    // A single TOTP account: secret "Hello!\xde\xad\xbe\xef", name "Example:alice@google.com", issuer "Example".
+    let payload = [
+        0x0a, 0x35, 0x0a, 0x0a, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x21, 0xde, 0xad, 0xbe, 0xef, 0x12, 0x18,
+        0x45, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x3a, 0x61, 0x6c, 0x69, 0x63, 0x65, 0x40, 0x67, 0x6f,
+        0x6f, 0x67, 0x6c, 0x65, 0x2e, 0x63, 0x6f, 0x6d, 0x1a, 0x07, 0x45, 0x78, 0x61, 0x6d, 0x70, 0x6c,
+        0x65, 0x20, 0x01, 0x28, 0x01, 0x30, 0x02, 0x10, 0x01, 0x18, 0x01, 0x20, 0x00, 0x28, 0xc3, 0xc4,
+        0xe4, 0xb9, 0x04,
+    ];
+    let entries = HOTP::from_google_migration_payload(&payload).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, OtpUriKind::Totp { period: 30 });
+    assert_eq!(entries[0].secret.get_secret_base32(), "JBSWY3DPEHPK3PXP");
+    assert_eq!(entries[0].secret.algorithm, HOTPAlgorithm::HMACSHA1);
+    assert_eq!(entries[0].account, "alice@google.com");
+    assert_eq!(entries[0].issuer.as_deref(), Some("Example"));
+    assert_eq!(entries[0].digits, Digits::SIX);
+
+    // provisioning URIs for the account transferred out of Google Authenticator
+    let totp_uri = entries[0].to_totp().unwrap().to_otpauth_uri_with_meta(&entries[0].meta());
+    assert_eq!(totp_uri, "otpauth://totp/Example:alice%40google.com?secret=JBSWY3DPEHPK3PXP&issuer=Example&algorithm=SHA1&digits=6&period=30");
+    let hotp_uri = entries[0].secret.to_otpauth_uri_with_meta(&entries[0].meta(), 0);
+    assert_eq!(hotp_uri, "otpauth://hotp/Example:alice%40google.com?secret=JBSWY3DPEHPK3PXP&issuer=Example&algorithm=SHA1&digits=6&counter=0");
+
+    let mut hotp: OtpUri = "otpauth://hotp/bob?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&algorithm=SHA256&digits=8&counter=300".parse().unwrap();
+    hotp.image = None;
+    let exported = HOTP::to_google_migration_payload(&[entries[0].clone(), hotp.clone()]).unwrap();
+    assert_eq!(HOTP::from_google_migration_payload(&exported).unwrap(), vec![entries[0].clone(), hotp]);
+
+    let sha3: OtpUri = "otpauth://totp/bob?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&algorithm=SHA3-256".parse().unwrap();
+    assert_eq!(HOTP::to_google_migration_payload(&[sha3]), Err(OtpError::UnknownAlgorithm));
+    let period: OtpUri = "otpauth://totp/bob?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&period=60".parse().unwrap();
+    assert_eq!(HOTP::to_google_migration_payload(&[period]), Err(OtpError::InvalidTimeStep));
+
+    assert_eq!(HOTP::from_google_migration_payload(&payload[..20]), Err(OtpError::InvalidMigrationPayload));
+    assert_eq!(HOTP::from_google_migration_payload(&[]), Ok(vec![]));
+}
+
+#[test]
+#[cfg(feature = "aegis")]
+fn test_aegis_json() {
+    let plain = r#"{"version": 1, "header": {"slots": null, "params": null}, "db": {"version": 2, "entries": [
+        {"type": "totp", "uuid": "3ae6f1ad-2e65-4ed2-a953-1ec0dff2386d", "name": "Mark", "issuer": "Deno", "note": "", "favorite": false, "icon": null,
+         "info": {"secret": "4SJHB4GSD43FZBAI7C2HLRJGPQ", "algo": "SHA1", "digits": 6, "period": 30}},
+        {"type": "hotp", "uuid": "9c9f2c6b-8d5f-4a2e-9f5a-1d0a6c6e2b3a", "name": "Mason", "issuer": "", "note": "", "favorite": false, "icon": null,
+         "info": {"secret": "YOOMIXWS5GN6RTBPUFFWKTW5M4", "algo": "SHA256", "digits": 8, "counter": 10}}
+    ], "groups": []}}"#;
+    let entries = HOTP::from_aegis_json(plain).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].kind, OtpUriKind::Totp { period: 30 });
+    assert_eq!(entries[0].secret.get_secret_base32(), "4SJHB4GSD43FZBAI7C2HLRJGPQ======");
+    assert_eq!(entries[0].account, "Mark");
+    assert_eq!(entries[0].issuer.as_deref(), Some("Deno"));
+    assert_eq!(entries[1].kind, OtpUriKind::Hotp { counter: 10 });
+    assert_eq!(entries[1].secret.algorithm, HOTPAlgorithm::HMACSHA256);
+    assert_eq!(entries[1].issuer, None);
+    assert_eq!(entries[1].digits, Digits::EIGHT);
+
+    let exported = HOTP::to_aegis_json(&entries).unwrap();
+    assert_eq!(HOTP::from_aegis_json(&exported).unwrap(), entries);
+
+    let encrypted = r#"{"version": 1, "header": {"slots": [{"type": 1, "uuid": "a8325752-c1be-458a-9b3e-5e0a8154d9ec", "key": "5abbbc6801cefd890bb820d5fcd87e3564610e4c6ec812696dc5002977c65442", "key_params": {"nonce": "000102030405060708090a0b", "tag": "9e0d4307c344989cb188754df49e33bc"}, "n": 1024, "r": 8, "p": 1, "salt": "27ea9ae53fa2f08a8dcd201615a8229422647b3058f9f36b08f9457e62888be1", "repaired": true}], "params": {"nonce": "6465666768696a6b6c6d6e6f", "tag": "10500e6338681c73f510cbeceae9fbc8"}}, "db": "MzmoAwuaP/FQQGXI6ElK3yescnjiCYBQnfH3M9nX3Djxy3rgbmb0cI3RVB/fN7+mf3mixwJ5IE3Ml523i6yX7z659jqzLwko6lbYcDy7VIVUHHlSlfa18LvTGuRZfS8861OoXkkA2QzY2PLlgGTLm1dO8j37WZtw1xf8k/aUKqld6/2KUxJgCeVpdGPo9jPw/7OYZGpr4PNd4hthwDPMmf9fBWawspSMB3SB1YUaxaG0y+3SFcA3rApkZWr4oD36mWVOqJxSJ0V2TC05B3u7PwN63k7VEkvtozou9M6EfYwhBZjbgNZjfyuOcPGKMem2OaJ0icnk2+fn0moOUFeV/1VJqrNNVOn6nXP08IHWezKSq29CyksAR/VjZCcaEyBR6dQ3Uhtc29oDrjSxy5PjzSolOtssKHe+L7hDK7LgDR/DZ4jDeTtwzq74IVv7FAIHjcgpcuRxgraW+oukRsW9Y7/29KRINRrUkbPaii5gy0Im64Z7v+SJ1nB4jdFMr8DRXXkuJsf27BKxqxP6Nf6AtbhJamH9WrqWxz5XT9M+RRMFZwIOl1HeHKFoyafQTbcmIdso0OlQK7NArcXKNTqjB2doESuTJmBaXLOXbn4dPDT7SozcaUSdcH5rTS6q1YrhGmDOLCwPysjNRt3h76btSUeK6XPO9yhrQQq4tN7tdquZP7KbFg=="}"#;
+    assert_eq!(HOTP::from_aegis_json_encrypted(encrypted, "test").unwrap(), entries);
+    assert_eq!(HOTP::from_aegis_json_encrypted(encrypted, "wrong"), Err(OtpError::VaultDecryption));
+    assert_eq!(HOTP::from_aegis_json(encrypted), Err(OtpError::InvalidVault));
+    assert_eq!(HOTP::from_aegis_json_encrypted(plain, "test"), Err(OtpError::InvalidVault));
+    assert_eq!(HOTP::from_aegis_json_encrypted(&encrypted.replace("\"n\": 1024", "\"n\": 1073741824"), "test"), Err(OtpError::InvalidVault));
+    assert_eq!(HOTP::from_aegis_json_encrypted(&encrypted.replace("\"r\": 8", "\"r\": 64"), "test"), Err(OtpError::InvalidVault));
+    assert_eq!(HOTP::from_aegis_json_encrypted(&encrypted.replace("\"p\": 1", "\"p\": 32"), "test"), Err(OtpError::InvalidVault));
+    assert_eq!(HOTP::from_aegis_json("{}"), Err(OtpError::InvalidVault));
+
+    for digits in ["0", "99"] {
+        let invalid = plain.replace("\"digits\": 6", &format!("\"digits\": {}", digits));
+        assert_eq!(HOTP::from_aegis_json(&invalid), Err(OtpError::InvalidVault));
+    }
+}
+
+proptest::proptest! {
+    #[test]
+    fn prop_hotp_otp_has_at_most_digits(secret in proptest::collection::vec(proptest::num::u8::ANY, 16..=64), counter: u64, digits in 1u32..=10) {
+        let otp = HOTP { secret, algorithm: HOTPAlgorithm::HMACSHA256 }.get_otp_counter(counter, digits);
+        proptest::prop_assert!((otp as u64) < 10u64.pow(digits));
+    }
+
+    #[test]
+    fn prop_hotp_six_digits(counter: u64) {
+        proptest::prop_assert!(HOTP::rfc4226_test_secret().get_otp_counter(counter, 6) < 1_000_000);
+    }
+
+    #[test]
+    fn prop_num_to_buffer_is_big_endian(number: u64) {
+        proptest::prop_assert_eq!(u64::from_be_bytes(utils::num_to_buffer(number)), number);
+    }
+
+    #[test]
+    fn prop_base32_round_trip(secret in proptest::collection::vec(proptest::num::u8::ANY, 16..=64)) {
+        let hotp = HOTP { secret, algorithm: HOTPAlgorithm::HMACSHA256 };
+        let decoded = HOTP::from_base32_predefined_algorithm(&hotp.get_secret_base32(), hotp.algorithm).unwrap();
+        proptest::prop_assert_eq!(decoded, hotp);
+    }
+
+    #[test]
+    fn prop_hotp_value_clears_sign_bit(hmac in proptest::collection::vec(proptest::num::u8::ANY, 20..=64)) {
+        proptest::prop_assert_eq!(HOTP::get_hotp_value(&hmac) & 0x8000_0000, 0);
+    }
 }
@@ -0,0 +1,323 @@
+use super::*;
+
+/// Account metadata carried by an `otpauth://` URI alongside the secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpUriMeta {
+    /// The account name, e.g. the user's email address.
+    pub account: String,
+    /// The provider or service the account belongs to.
+    pub issuer: Option<String>,
+    /// OTP length in digits.
+    pub digits: Digits,
+    /// URL of an image shown by some authenticator apps next to the account.
+    pub image: Option<String>,
+}
+
+/// The OTP type of an `otpauth://` URI, with its type specific parameter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OtpUriKind {
+    /// `otpauth://totp/`, with the time step in seconds.
+    Totp { period: u64 },
+    /// `otpauth://hotp/`, with the counter of the next OTP.
+    Hotp { counter: u64 },
+}
+
+/// A parsed `otpauth://` provisioning URI, as scanned by Google Authenticator and compatible apps.
+///
+/// Parse it with `str::parse`, and format it back to a URI with `to_string`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpUri {
+    /// The OTP type and its type specific parameter.
+    pub kind: OtpUriKind,
+    /// The shared secret and the HMAC algorithm.
+    pub secret: HOTP,
+    /// The account name, e.g. the user's email address.
+    pub account: String,
+    /// The provider or service the account belongs to.
+    pub issuer: Option<String>,
+    /// OTP length in digits.
+    pub digits: Digits,
+    /// URL of an image shown by some authenticator apps next to the account.
+    pub image: Option<String>,
+}
+
+impl OtpUri {
+    /// Creates the `TOTP` described by this URI, including its issuer and account name.
+    ///
+    /// Returns `OtpError::InvalidUri` for `otpauth://hotp/` URIs.
+    #[cfg(feature = "std")]
+    pub fn to_totp(&self) -> Result<TOTP, OtpError> {
+        let period = match self.kind {
+            OtpUriKind::Totp { period } => period,
+            OtpUriKind::Hotp { .. } => {
+                return Err(OtpError::InvalidUri);
+            }
+        };
+
+        let mut totp = TOTP::new(self.secret.clone(), TimeStep::new(period)?, 0);
+        totp.issuer = self.issuer.clone();
+        totp.account_name = Some(self.account.clone());
+        Ok(totp)
+    }
+
+    /// Creates the `HOTPCounter` described by this URI.
+    ///
+    /// Returns `OtpError::InvalidUri` for `otpauth://totp/` URIs.
+    pub fn to_hotp_counter(&self) -> Result<HOTPCounter, OtpError> {
+        match self.kind {
+            OtpUriKind::Hotp { counter } => Ok(HOTPCounter::new(self.secret.clone(), counter)),
+            OtpUriKind::Totp { .. } => Err(OtpError::InvalidUri),
+        }
+    }
+
+    /// Returns the account metadata of this URI.
+    pub fn meta(&self) -> OtpUriMeta {
+        OtpUriMeta {
+            account: self.account.clone(),
+            issuer: self.issuer.clone(),
+            digits: self.digits,
+            image: self.image.clone(),
+        }
+    }
+}
+
+impl core::str::FromStr for OtpUri {
+    type Err = OtpError;
+
+    /// Parses an `otpauth://totp/` or `otpauth://hotp/` URI.
+    ///
+    /// Missing parameters default to the RFC recommended values: SHA1, 6 digits and a 30 seconds time step.
+    /// HOTP URIs must provide a `counter`.
+    fn from_str(uri: &str) -> Result<OtpUri, OtpError> {
+        let rest = uri.strip_prefix("otpauth://").ok_or(OtpError::InvalidUri)?;
+        let (kind, rest) = rest.split_once('/').ok_or(OtpError::InvalidUri)?;
+        if kind != "totp" && kind != "hotp" {
+            return Err(OtpError::InvalidUri);
+        }
+
+        let (label, query) = match rest.split_once('?') {
+            Some((label, query)) => (label, query),
+            None => (rest, ""),
+        };
+
+        let label = utils::percent_decode(label).ok_or(OtpError::InvalidUri)?;
+        let (mut issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(String::from(issuer)), String::from(account.trim_start())),
+            None => (None, label),
+        };
+
+        let mut secret = None;
+        let mut algorithm = HOTPAlgorithm::default();
+        let mut digits = Digits::SIX;
+        let mut period = DEFAULT_TIME_STEP;
+        let mut counter = None;
+        let mut image = None;
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or(OtpError::InvalidUri)?;
+            let value = utils::percent_decode(value).ok_or(OtpError::InvalidUri)?;
+
+            match key {
+                "secret" => secret = Some(value),
+                "issuer" => issuer = Some(value),
+                "algorithm" => algorithm = value.parse()?,
+                "digits" => {
+                    let n = value.parse().map_err(|_| OtpError::InvalidUri)?;
+                    digits = Digits::try_new(n).map_err(|_| OtpError::InvalidUri)?;
+                },
+                "period" => period = value.parse().map_err(|_| OtpError::InvalidUri)?,
+                "counter" => counter = Some(value.parse().map_err(|_| OtpError::InvalidUri)?),
+                "image" => image = Some(value),
+                _ => {},
+            }
+        }
+
+        let kind = if kind == "totp" {
+            if period == 0 {
+                return Err(OtpError::InvalidUri);
+            }
+            OtpUriKind::Totp { period }
+        } else {
+            OtpUriKind::Hotp { counter: counter.ok_or(OtpError::InvalidUri)? }
+        };
+
+        let secret = secret.ok_or(OtpError::InvalidUri)?;
+
+        Ok(OtpUri {
+            kind,
+            secret: decode_uri_secret(&secret, algorithm)?,
+            account,
+            issuer,
+            digits,
+            image,
+        })
+    }
+}
+
+/// Decodes the base32 secret of a URI, which authenticator apps commonly write lowercase and without padding.
+fn decode_uri_secret(secret: &str, algorithm: HOTPAlgorithm) -> Result<HOTP, OtpError> {
+    let mut secret = secret.to_ascii_uppercase();
+    while !secret.len().is_multiple_of(8) {
+        secret.push('=');
+    }
+
+    HOTP::from_base32_predefined_algorithm(&secret, algorithm)
+}
+
+impl core::fmt::Display for OtpUri {
+    /// Formats the URI with its label and parameters percent-encoded, and the secret as unpadded base32.
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.kind {
+            OtpUriKind::Totp { .. } => write!(f, "otpauth://totp/")?,
+            OtpUriKind::Hotp { .. } => write!(f, "otpauth://hotp/")?,
+        }
+
+        if let Some(issuer) = &self.issuer {
+            write!(f, "{}:", utils::percent_encode(issuer))?;
+        }
+        write!(f, "{}", utils::percent_encode(&self.account))?;
+
+        write!(f, "?secret={}", self.secret.get_secret_base32().trim_end_matches('='))?;
+        if let Some(issuer) = &self.issuer {
+            write!(f, "&issuer={}", utils::percent_encode(issuer))?;
+        }
+        write!(f, "&algorithm={}&digits={}", self.secret.algorithm, self.digits.get())?;
+
+        match self.kind {
+            OtpUriKind::Totp { period } => write!(f, "&period={}", period)?,
+            OtpUriKind::Hotp { counter } => write!(f, "&counter={}", counter)?,
+        }
+        if let Some(image) = &self.image {
+            write!(f, "&image={}", utils::percent_encode(image))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: TimeProvider> TOTP<T> {
+    /// Creates the `otpauth://totp/` URI describing this TOTP.
+    ///
+    /// # Arguments
+    /// * `meta` - The account metadata to include in the URI.
+    ///
+    /// # Notes
+    /// The URI format has no representation for T0, so `start_time` is not included.
+    pub fn to_uri(&self, meta: &OtpUriMeta) -> OtpUri {
+        OtpUri {
+            kind: OtpUriKind::Totp { period: self.time_step },
+            secret: self.secret.clone(),
+            account: meta.account.clone(),
+            issuer: meta.issuer.clone(),
+            digits: meta.digits,
+            image: meta.image.clone(),
+        }
+    }
+
+    /// Generates an `otpauth://totp/` provisioning URI with all the account metadata, including the image.
+    ///
+    /// The OTP length is taken from `meta.digits` and the period from this TOTP's time step, rather than from
+    /// separate arguments, so the URI always describes the codes this TOTP generates. The label and parameters are
+    /// written in the order used by Google Authenticator, the secret as unpadded base32.
+    ///
+    /// # Arguments
+    /// * `meta` - The account metadata to include in the URI.
+    pub fn to_otpauth_uri_with_meta(&self, meta: &OtpUriMeta) -> String {
+        self.to_uri(meta).to_string()
+    }
+
+    /// Generates an `otpauth://totp/` provisioning URI, as scanned by Google Authenticator and compatible apps.
+    ///
+    /// # Arguments
+    /// * `label` - The account name shown by the authenticator app.
+    /// * `issuer` - The provider or service the account belongs to.
+    /// * `digits` - OTP length in digits.
+    ///
+    /// # Notes
+    /// The URI format has no representation for T0, so `start_time` is not included.
+    pub fn to_otpauth_uri(&self, label: &str, issuer: Option<&str>, digits: Digits) -> String {
+        self.to_uri(&OtpUriMeta {
+            account: String::from(label),
+            issuer: issuer.map(String::from),
+            digits,
+            image: None,
+        }).to_string()
+    }
+
+    /// Generates a provisioning URI following Google's Key URI format, see
+    /// <https://github.com/google/google-authenticator/wiki/Key-Uri-Format>.
+    ///
+    /// The label is `issuer:account`, spaces and other reserved characters are percent-encoded as `%20` etc,
+    /// and the parameters are ordered `secret`, `issuer`, `algorithm`, `digits` and `period`.
+    /// Returns `OtpError::InvalidUri` if `account` or `issuer` contain a colon, which would corrupt the label.
+    ///
+    /// # Arguments
+    /// * `account` - The account name shown by the authenticator app.
+    /// * `issuer` - The provider or service the account belongs to.
+    /// * `digits` - OTP length in digits.
+    pub fn to_keyuri_string(&self, account: &str, issuer: &str, digits: Digits) -> Result<String, OtpError> {
+        if account.contains(':') || issuer.contains(':') {
+            return Err(OtpError::InvalidUri);
+        }
+
+        Ok(self.to_otpauth_uri(account, Some(issuer), digits))
+    }
+}
+
+impl HOTP {
+    /// Generates an `otpauth://hotp/` provisioning URI with all the account metadata, including the image.
+    ///
+    /// The OTP length is taken from `meta.digits`. HOTP URIs have no period, they carry the `counter` instead.
+    ///
+    /// # Arguments
+    /// * `meta` - The account metadata to include in the URI.
+    /// * `counter` - The counter of the next OTP.
+    pub fn to_otpauth_uri_with_meta(&self, meta: &OtpUriMeta, counter: u64) -> String {
+        OtpUri {
+            kind: OtpUriKind::Hotp { counter },
+            secret: self.clone(),
+            account: meta.account.clone(),
+            issuer: meta.issuer.clone(),
+            digits: meta.digits,
+            image: meta.image.clone(),
+        }.to_string()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TOTP {
+    /// Parses an `otpauth://totp/` provisioning URI.
+    ///
+    /// Missing parameters default to the RFC recommended values: SHA1, 6 digits and a 30 seconds time step.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI to parse.
+    pub fn from_otpauth_uri(uri: &str) -> Result<(TOTP, OtpUriMeta), OtpError> {
+        let uri: OtpUri = uri.parse()?;
+        Ok((uri.to_totp()?, uri.meta()))
+    }
+
+    /// Parses an `otpauth://totp/` URI following Google's Key URI format, the inverse of `to_keyuri_string`.
+    ///
+    /// Labels and parameters are percent-decoded, the `issuer` parameter takes precedence over the label prefix,
+    /// and the secret may be padded or not. Missing parameters default to SHA1, 6 digits and 30 seconds.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI to parse.
+    pub fn from_keyuri_string(uri: &str) -> Result<(TOTP, OtpUriMeta), OtpError> {
+        TOTP::from_otpauth_uri(uri)
+    }
+}
+
+impl HOTPCounter {
+    /// Parses an `otpauth://hotp/` URI following Google's Key URI format, which must provide a `counter`.
+    ///
+    /// Labels and parameters are percent-decoded, the `issuer` parameter takes precedence over the label prefix,
+    /// and the secret may be padded or not. Missing parameters default to SHA1 and 6 digits.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI to parse.
+    pub fn from_hotp_keyuri_string(uri: &str) -> Result<(HOTPCounter, OtpUriMeta), OtpError> {
+        let uri: OtpUri = uri.parse()?;
+        Ok((uri.to_hotp_counter()?, uri.meta()))
+    }
+}
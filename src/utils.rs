@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 
 fn get_byte_at(num: u64, idx: u32) -> u8 {
     let bits_offset = idx * 8;
@@ -21,7 +24,141 @@ pub fn num_to_buffer(number: u64) -> [u8; 8] {
     ]
 }
 
-#[cfg(tests)]
+/// Percent-encodes everything except the unreserved characters of RFC3986.
+pub fn percent_encode(input: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut output = String::with_capacity(input.len());
+    for &byte in input.as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => output.push(byte as char),
+            _ => {
+                output.push('%');
+                output.push(HEX[(byte >> 4) as usize] as char);
+                output.push(HEX[(byte & 0x0f) as usize] as char);
+            }
+        }
+    }
+    output
+}
+
+/// Decodes a percent-encoded string, returns `None` for malformed escapes or invalid UTF-8.
+pub fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            let hex = bytes.get(idx + 1..idx + 3)?;
+            let hex = core::str::from_utf8(hex).ok()?;
+            output.push(u8::from_str_radix(hex, 16).ok()?);
+            idx += 3;
+        } else {
+            output.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+
+    String::from_utf8(output).ok()
+}
+
+/// Crockford's base32 alphabet, followed by the five symbols only used as check symbols.
+pub const CROCKFORD_ALPHABET: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// Encodes with Crockford's base32 alphabet, upper case and without a check symbol.
+pub fn crockford_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity((input.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in input {
+        buffer = ((buffer << 8) | byte as u32) & 0x1fff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(CROCKFORD_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(CROCKFORD_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+/// Decodes Crockford's base32, ignoring case and hyphens and reading `O` as 0 and `I`, `L` as 1.
+///
+/// A trailing check symbol is verified when present. When the input decodes both with and without
+/// its last symbol, it is read without a check symbol.
+pub fn crockford_decode(input: &str) -> Option<Vec<u8>> {
+    let symbols = input.bytes()
+        .filter(|c| *c != b'-')
+        .map(crockford_symbol)
+        .collect::<Option<Vec<u8>>>()?;
+    let (check, data) = symbols.split_last()?;
+
+    if *check < 32 {
+        if let Some(output) = crockford_decode_symbols(&symbols) {
+            return Some(output);
+        }
+    }
+
+    let checksum = data.iter().fold(0u32, |acc, v| (acc * 32 + *v as u32) % 37);
+    if checksum != *check as u32 {
+        return None;
+    }
+    crockford_decode_symbols(data)
+}
+
+pub fn crockford_symbol(c: u8) -> Option<u8> {
+    match c.to_ascii_uppercase() {
+        b'O' => Some(0),
+        b'I' | b'L' => Some(1),
+        c => CROCKFORD_ALPHABET.iter().position(|s| *s == c).map(|v| v as u8),
+    }
+}
+
+fn crockford_decode_symbols(symbols: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(symbols.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &symbol in symbols {
+        if symbol >= 32 {
+            return None;
+        }
+        buffer = ((buffer << 5) | symbol as u32) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    // the trailing bits only pad the last symbol, a whole unused symbol or set padding bits are invalid.
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(output)
+}
+
+/// Computes an HMAC with a RustCrypto digest, for the algorithms `ring` does not implement.
+pub fn hmac_sign<D: hmac::digest::Digest + hmac::digest::core_api::BlockSizeUser>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    use hmac::Mac;
+
+    let mut mac = hmac::SimpleHmac::<D>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Compares two buffers without short-circuiting on the first mismatching byte.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    use subtle::ConstantTimeEq;
+
+    a.ct_eq(b).into()
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -40,4 +177,34 @@ mod tests {
 
         assert_eq!(&num_to_buffer(NUMBER)[..], &[0x0f, 0, 0, 0, 0, 0, 0, 0x01]);
     }
+
+    #[test]
+    fn test_percent_encoding() {
+        assert_eq!(percent_encode("Example Co:alice@example.com"), "Example%20Co%3Aalice%40example.com");
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        assert_eq!(percent_decode("Example%20Co%3aalice%40example.com").unwrap(), "Example Co:alice@example.com");
+        assert!(percent_decode("100%").is_none());
+    }
+
+    #[test]
+    fn test_crockford() {
+        assert_eq!(crockford_encode(b"12345678901234567890"), "64S36D1N6RVKGE9G64S36D1N6RVKGE9G");
+        assert_eq!(crockford_encode(&[0xff, 0x01]), "ZW0G");
+        assert_eq!(crockford_decode("zw0g").unwrap(), [0xff, 0x01]);
+        assert_eq!(crockford_decode("64s3-6dIn-6rvk-ge9g-64s3-6d1n-6rvk-ge9g").unwrap(), b"12345678901234567890");
+        assert_eq!(crockford_decode("64S36D1N6RVKGE9G64S36D1N6RVKGE9GA").unwrap(), b"12345678901234567890");
+        assert!(crockford_decode("64S36D1N6RVKGE9G64S36D1N6RVKGE9GB").is_none());
+        assert!(crockford_decode("ZW0H").is_none());
+        assert!(crockford_decode("ZWU0G").is_none());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"123456", b"123456"));
+        assert!(!constant_time_eq(b"123456", b"123457"));
+        assert!(!constant_time_eq(b"123456", b"0123456"));
+    }
 }
\ No newline at end of file
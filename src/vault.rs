@@ -0,0 +1,125 @@
+use super::*;
+use alloc::collections::BTreeMap;
+
+/// An OTP account stored in an `OtpVault`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpEntry {
+    /// The secret and OTP parameters of the account, including its issuer.
+    pub uri: OtpUri,
+    /// The name shown to the user for this account.
+    pub display_name: String,
+    /// Unix time in seconds of the last OTP generated by `OtpVault::get_otp`, `None` if it was never used.
+    pub last_used: Option<u64>,
+}
+
+impl OtpEntry {
+    /// Creates an account that was never used.
+    ///
+    /// # Arguments
+    /// * `uri` - The secret and OTP parameters of the account.
+    /// * `display_name` - The name shown to the user for this account.
+    pub fn new(uri: OtpUri, display_name: &str) -> OtpEntry {
+        OtpEntry {
+            uri,
+            display_name: String::from(display_name),
+            last_used: None,
+        }
+    }
+
+    /// Returns the provider or service the account belongs to.
+    pub fn issuer(&self) -> Option<&str> {
+        self.uri.issuer.as_deref()
+    }
+}
+
+/// A collection of OTP accounts looked up by name, as managed by authenticator apps.
+///
+/// Entries are kept sorted by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OtpVault {
+    entries: BTreeMap<String, OtpEntry>,
+}
+
+impl OtpVault {
+    /// Creates an empty vault.
+    pub fn new() -> OtpVault {
+        OtpVault::default()
+    }
+
+    /// Adds an account, replacing any account stored under the same name.
+    ///
+    /// # Arguments
+    /// * `name` - The name to look the account up by.
+    /// * `entry` - The account.
+    pub fn add(&mut self, name: &str, entry: OtpEntry) {
+        self.entries.insert(String::from(name), entry);
+    }
+
+    /// Removes an account, returning it if it existed.
+    ///
+    /// # Arguments
+    /// * `name` - The name the account is stored under.
+    pub fn remove(&mut self, name: &str) -> Option<OtpEntry> {
+        self.entries.remove(name)
+    }
+
+    /// Returns the account stored under `name`.
+    pub fn get(&self, name: &str) -> Option<&OtpEntry> {
+        self.entries.get(name)
+    }
+
+    /// Generates the current OTP of an account and records the current time as its `last_used`.
+    ///
+    /// TOTP accounts use the current time frame, HOTP accounts their stored counter without advancing it.
+    /// Returns `None` if there's no account named `name`, or `digits` is not between 1 and 10.
+    ///
+    /// # Arguments
+    /// * `name` - The name the account is stored under.
+    /// * `digits` - Desired OTP length, should be at least 6.
+    #[cfg(feature = "std")]
+    pub fn get_otp(&mut self, name: &str, digits: u32) -> Option<u32> {
+        Digits::try_new(digits).ok()?;
+        let now = SystemTimeClock.unix_seconds().ok()?;
+        let entry = self.entries.get_mut(name)?;
+        let otp = match entry.uri.kind {
+            OtpUriKind::Totp { .. } => entry.uri.to_totp().ok()?.get_otp_at(now, digits),
+            OtpUriKind::Hotp { counter } => entry.uri.secret.get_otp_counter(counter, digits),
+        };
+        entry.last_used = Some(now);
+        Some(otp)
+    }
+
+    /// Iterates over the accounts and their names, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &OtpEntry)> {
+        self.entries.iter().map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    /// Returns the amount of accounts in the vault.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the vault has no accounts.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Exports the vault as a JSON object, mapping account names to their `otpauth://` URI, display name and
+    /// last use.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, OtpError> {
+        serde_json::to_string(&self.entries).map_err(|_| OtpError::InvalidVault)
+    }
+
+    /// Loads a vault exported by `to_json`.
+    ///
+    /// # Arguments
+    /// * `json` - A JSON object mapping account names to accounts, as written by `to_json`.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<OtpVault, OtpError> {
+        match serde_json::from_str(json) {
+            Ok(entries) => Ok(OtpVault { entries }),
+            Err(_) => Err(OtpError::InvalidVault),
+        }
+    }
+}